@@ -0,0 +1,193 @@
+use magic_eden_sft_core::prices::{jupiter_price_url, JupiterPrice};
+use magic_eden_sft_core::rpc::{
+    decode_accounts, decode_token_balances, get_multiple_accounts_request, get_token_accounts_by_owner_request,
+    JsonRpcResult, UiAccount,
+};
+use magic_eden_sft_core::{
+    decode_dex_state, decode_orderbook_state, FetchError, Listings, MagicEdenItem, MarketAccounts,
+    MarketOrders, Side, FLIPSIDE_TRADES_URL,
+};
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Blocking counterpart of the web app's RPC client, talking to a single
+/// endpoint
+pub struct RpcClient {
+    url: String,
+}
+
+impl RpcClient {
+    pub fn new(url: String) -> Self {
+        Self { url }
+    }
+
+    pub fn get_multiple_accounts(
+        &self,
+        addresses: &[String],
+    ) -> Result<Vec<Option<UiAccount>>, FetchError> {
+        let mut accounts = Vec::with_capacity(addresses.len());
+
+        for chunk in addresses.chunks(100) {
+            let body = get_multiple_accounts_request(chunk);
+
+            accounts.extend(decode_accounts(send_json(ureq::post(&self.url), Some(&body))?)?);
+        }
+
+        Ok(accounts)
+    }
+
+    /// Accounts exactly as the node sent them, used to record fixtures
+    pub fn get_multiple_accounts_raw(&self, addresses: &[String]) -> Result<Vec<Value>, FetchError> {
+        let mut accounts = Vec::with_capacity(addresses.len());
+
+        for chunk in addresses.chunks(100) {
+            let body = get_multiple_accounts_request(chunk);
+            let res: JsonRpcResult<Vec<Value>> = send_json(ureq::post(&self.url), Some(&body))?;
+
+            accounts.extend(res.into_value()?);
+        }
+
+        Ok(accounts)
+    }
+
+    /// Token balances of the owner, by mint
+    pub fn get_token_balances(&self, owner: &str) -> Result<HashMap<String, Decimal>, FetchError> {
+        let body = get_token_accounts_by_owner_request(owner);
+
+        decode_token_balances(send_json(ureq::post(&self.url), Some(&body))?)
+    }
+
+    /// Resolves the accounts of every market, skipping the ones that can't be
+    /// found on chain
+    pub fn load_market_accounts<'a>(
+        &self,
+        items: &[&'a MagicEdenItem],
+    ) -> Result<Vec<(&'a MagicEdenItem, MarketAccounts)>, FetchError> {
+        let addresses = items
+            .iter()
+            .map(|item| item.market_address.to_string())
+            .collect::<Vec<_>>();
+
+        let dex_states = self
+            .get_multiple_accounts(&addresses)?
+            .into_iter()
+            .map(|account| decode_dex_state(&account?.data).copied())
+            .collect::<Vec<_>>();
+
+        let addresses = dex_states
+            .iter()
+            .flatten()
+            .map(|dex_state| dex_state.orderbook.to_string())
+            .collect::<Vec<_>>();
+
+        let mut orderbooks = self.get_multiple_accounts(&addresses)?.into_iter();
+
+        let mut results = vec![];
+        for (item, dex_state) in items.iter().zip(dex_states) {
+            let dex_state = match dex_state {
+                Some(dex_state) => dex_state,
+                None => {
+                    eprintln!("Market {} not found", item.market_address);
+                    continue;
+                }
+            };
+
+            let orderbook = orderbooks.next().flatten();
+            match orderbook.as_ref().and_then(|account| decode_orderbook_state(&account.data)) {
+                Some(orderbook_state) => results.push((
+                    *item,
+                    MarketAccounts::new(item.market_address, &dex_state, orderbook_state),
+                )),
+                None => eprintln!("Orderbook {} not found", dex_state.orderbook),
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Orders of each market, `None` for the markets whose slabs can't be
+    /// decoded, which are reported on stderr so the rest still load
+    pub fn load_orders(&self, markets: &[&MarketAccounts]) -> Result<Vec<Option<MarketOrders>>, FetchError> {
+        let addresses = markets
+            .iter()
+            .flat_map(|accounts| [accounts.asks.to_string(), accounts.bids.to_string()])
+            .collect::<Vec<_>>();
+
+        let mut slabs = self.get_multiple_accounts(&addresses)?.into_iter();
+
+        let mut decode = |address: String, side| -> Result<_, FetchError> {
+            let mut account = match slabs.next().flatten() {
+                Some(account) => account,
+                None => return Ok(vec![]),
+            };
+
+            Listings::from_buffer(&mut account.data, side)
+                .map(Listings::to_vec)
+                .map_err(|e| FetchError::SlabDecode {
+                    address,
+                    reason: e.to_string(),
+                })
+        };
+
+        let orders = markets
+            .iter()
+            .map(|accounts| {
+                // Both slabs are always decoded to keep in step with the
+                // accounts
+                let asks = decode(accounts.asks.to_string(), Side::Asks);
+                let bids = decode(accounts.bids.to_string(), Side::Bids);
+
+                match (asks, bids) {
+                    (Ok(asks), Ok(bids)) => Some(MarketOrders { bids, asks }),
+                    (Err(e), _) | (_, Err(e)) => {
+                        eprintln!("Skipping market {}: {e}", accounts.market);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Ok(orders)
+    }
+}
+
+/// Price of the token in `vs_token`
+pub fn jupiter_price(token: &str, vs_token: &str) -> Result<Decimal, FetchError> {
+    let res: JupiterPrice = send_json(ureq::get(&jupiter_price_url(token, vs_token)), None)?;
+
+    res.data
+        .get(token)
+        .map(|data| data.price)
+        .ok_or_else(|| FetchError::Missing(format!("{token} price")))
+}
+
+/// The Flipside trades response, as is
+pub fn flipside_trades() -> Result<Value, FetchError> {
+    send_json(ureq::get(FLIPSIDE_TRADES_URL), None)
+}
+
+fn send_json<T: DeserializeOwned>(request: ureq::Request, body: Option<&Value>) -> Result<T, FetchError> {
+    let res = match body {
+        Some(body) => request.send_json(body),
+        None => request.call(),
+    };
+
+    let res = match res {
+        Ok(res) => res,
+        Err(ureq::Error::Status(429, res)) => {
+            let retry_after = res
+                .header("Retry-After")
+                .and_then(|retry_after| retry_after.trim().parse().ok());
+
+            return Err(FetchError::RateLimited { retry_after });
+        }
+        Err(ureq::Error::Status(status, res)) => {
+            return Err(FetchError::Http(format!("{} {}", status, res.status_text())));
+        }
+        Err(e) => return Err(FetchError::Http(e.to_string())),
+    };
+
+    res.into_json().map_err(|e| FetchError::Json(e.to_string()))
+}
@@ -0,0 +1,449 @@
+mod client;
+mod output;
+
+use self::client::{flipside_trades, jupiter_price, RpcClient};
+use self::output::{Format, Output};
+use clap::{Parser, Subcommand};
+use dex_v4::state::UserAccount;
+use magic_eden_sft_core::prices::{GENE_MINT, KI_MINT, SOL_MINT, USDC_MINT};
+use magic_eden_sft_core::rpc::DEFAULT_RPC_URL;
+use magic_eden_sft_core::validation::validate_recipes;
+use magic_eden_sft_core::{
+    genopets_markets, genopets_recipes_json, FeeModel, FetchError, Listings, MagicEdenItem, Recipes, Side, CURRENCIES,
+};
+use rust_decimal::Decimal;
+use serde_json::{Map, Value};
+use solana_program::pubkey::Pubkey;
+use std::{fs, path::{Path, PathBuf}, process::ExitCode, str::FromStr};
+
+/// Query Genopets SFT markets on Magic Eden
+#[derive(Parser)]
+#[command(name = "sft-tracker")]
+struct Cli {
+    /// RPC endpoint to load the markets from
+    #[arg(long, global = true, default_value = DEFAULT_RPC_URL)]
+    rpc: String,
+    #[arg(long, global = true, value_enum, default_value_t = Format::Table)]
+    format: Format,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Every order of the markets matching a token address, market address or
+    /// name
+    Listings { item: String },
+    /// Best ask and bid of every market, optionally limited to one collection
+    Floor {
+        #[arg(long)]
+        collection: Option<String>,
+    },
+    /// KI and GENE needed to craft an item
+    RecipeCost {
+        token: String,
+        /// Also price the recipe in SOL using Jupiter
+        #[arg(long)]
+        sol: bool,
+    },
+    /// SOL waiting to be collected by an owner, per market
+    PendingCollect { owner: String },
+    /// Record the market accounts, KI/GENE prices and trades for the web app's
+    /// fixture data source
+    RecordFixtures {
+        #[arg(long, default_value = "fixtures")]
+        out: PathBuf,
+        /// Only record the first markets of the registry, to keep the
+        /// recording small enough to commit
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Also record the dex-v4 user accounts and token balances of an owner
+        #[arg(long)]
+        owner: Option<String>,
+    },
+    /// Capture the asks account of a market as a golden file for the slab
+    /// decoding tests
+    RecordGolden {
+        /// Token address, market address or exact name
+        item: String,
+        #[arg(long, default_value = "core/tests/golden")]
+        out: PathBuf,
+    },
+    /// Check recipe data for unknown reagents, bad amounts, cycles and items
+    /// missing from the market registry. Defaults to the bundled files
+    ValidateRecipes {
+        #[arg(long)]
+        recipes: Option<PathBuf>,
+        #[arg(long)]
+        markets: Option<PathBuf>,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let rpc = RpcClient::new(cli.rpc);
+    let markets = genopets_markets();
+
+    let res = match cli.command {
+        // Issues are printed like any other output, but fail the command
+        Command::ValidateRecipes { recipes, markets } => {
+            return match validate(recipes.as_deref(), markets.as_deref()) {
+                Ok(output) if output.is_empty() => {
+                    eprintln!("No issues found");
+
+                    ExitCode::SUCCESS
+                }
+                Ok(output) => {
+                    println!("{}", output.render(cli.format));
+
+                    ExitCode::FAILURE
+                }
+                Err(e) => {
+                    eprintln!("{e}");
+
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        Command::Listings { item } => listings(&rpc, &markets, &item),
+        Command::Floor { collection } => floor(&rpc, &markets, collection.as_deref()),
+        Command::RecipeCost { token, sol } => recipe_cost(&markets, &token, sol),
+        Command::PendingCollect { owner } => pending_collect(&rpc, &markets, &owner),
+        Command::RecordFixtures { out, limit, owner } => {
+            let markets = &markets[..limit.unwrap_or(markets.len()).min(markets.len())];
+
+            record_fixtures(&rpc, markets, owner.as_deref(), &out)
+        }
+        Command::RecordGolden { item, out } => record_golden(&rpc, &markets, &item, &out),
+    };
+
+    match res {
+        Ok(output) => {
+            println!("{}", output.render(cli.format));
+
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn listings(rpc: &RpcClient, markets: &[MagicEdenItem], item: &str) -> Result<Output, FetchError> {
+    let search = item.to_lowercase();
+    let items = markets
+        .iter()
+        .filter(|market| {
+            market.token_address == item
+                || market.market_address.to_string() == item
+                || market.token_title.to_lowercase().contains(&search)
+        })
+        .collect::<Vec<_>>();
+
+    if items.is_empty() {
+        return Err(FetchError::Missing(format!("market matching {item}")));
+    }
+
+    let accounts = rpc.load_market_accounts(&items)?;
+    let orders = rpc.load_orders(&accounts.iter().map(|(_, accounts)| accounts).collect::<Vec<_>>())?;
+
+    let mut output = Output::new(vec!["market", "title", "side", "price", "quantity", "owner"]);
+
+    // Markets that failed to decode were already reported
+    let markets = accounts.iter().zip(orders).filter_map(|(market, orders)| Some((market, orders?)));
+
+    for ((item, accounts), orders) in markets {
+        let sides = [
+            ("ask", orders.asks, FeeModel::buyer_pays as fn(&FeeModel, u64) -> Decimal),
+            ("bid", orders.bids, FeeModel::seller_receives),
+        ];
+
+        for (side, listings, ui_price) in sides {
+            for listing in listings {
+                output.push(vec![
+                    item.market_address.to_string(),
+                    item.token_title.clone(),
+                    side.to_string(),
+                    ui_price(&accounts.fees, listing.price).round_dp(4).to_string(),
+                    listing.base_quantity.to_string(),
+                    listing.owner.to_string(),
+                ]);
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+fn floor(rpc: &RpcClient, markets: &[MagicEdenItem], collection: Option<&str>) -> Result<Output, FetchError> {
+    let items = markets
+        .iter()
+        .filter(|item| collection.map_or(true, |collection| item.collection == collection))
+        .collect::<Vec<_>>();
+
+    let accounts = rpc.load_market_accounts(&items)?;
+    let orders = rpc.load_orders(&accounts.iter().map(|(_, accounts)| accounts).collect::<Vec<_>>())?;
+
+    let mut output = Output::new(vec!["token", "title", "collection", "floor", "floor_quantity", "best_bid"]);
+
+    // Markets that failed to decode were already reported
+    let markets = accounts.iter().zip(orders).filter_map(|(market, orders)| Some((market, orders?)));
+
+    for ((item, accounts), orders) in markets {
+        let best_ask = orders.best_ask();
+        let best_bid = orders.best_bid();
+
+        output.push(vec![
+            item.token_address.clone(),
+            item.token_title.clone(),
+            item.collection.clone(),
+            best_ask
+                .map(|listing| accounts.fees.buyer_pays(listing.price).round_dp(4).to_string())
+                .unwrap_or_default(),
+            best_ask
+                .map(|listing| listing.base_quantity.to_string())
+                .unwrap_or_default(),
+            best_bid
+                .map(|listing| accounts.fees.seller_receives(listing.price).round_dp(4).to_string())
+                .unwrap_or_default(),
+        ]);
+    }
+
+    Ok(output)
+}
+
+fn recipe_cost(markets: &[MagicEdenItem], token: &str, sol: bool) -> Result<Output, FetchError> {
+    let recipes = Recipes::new(markets.to_vec());
+    let recipe = recipes
+        .get(token)
+        .ok_or_else(|| FetchError::Missing(format!("recipe for {token}")))?;
+
+    let mut output = Output::new(vec!["item", "amount"]);
+
+    for (reagent, amount) in &recipe.reagents {
+        output.push(vec![reagent.clone(), amount.to_string()]);
+    }
+
+    output.push(vec![String::from("Total KI"), recipe.ki_cost.to_string()]);
+    output.push(vec![String::from("Total GENE"), recipe.gene_cost.to_string()]);
+
+    if sol {
+        let total_cost = recipe.ki_cost * jupiter_price(KI_MINT, SOL_MINT)?
+            + recipe.gene_cost * jupiter_price(GENE_MINT, SOL_MINT)?;
+
+        output.push(vec![String::from("Total SOL"), total_cost.round_dp(4).to_string()]);
+    }
+
+    Ok(output)
+}
+
+fn pending_collect(rpc: &RpcClient, markets: &[MagicEdenItem], owner: &str) -> Result<Output, FetchError> {
+    let owner = Pubkey::from_str(owner).map_err(|e| FetchError::InvalidInput(format!("owner address {owner}: {e}")))?;
+
+    let addresses = markets
+        .iter()
+        .map(|item| item.user_account_address(&owner).to_string())
+        .collect::<Vec<_>>();
+
+    let mut output = Output::new(vec!["market", "title", "pending_sol"]);
+
+    for (item, account) in markets.iter().zip(rpc.get_multiple_accounts(&addresses)?) {
+        let mut account = match account {
+            Some(account) => account,
+            None => continue,
+        };
+
+        let header = match UserAccount::from_buffer(&mut account.data) {
+            Ok(account) => account.header.to_owned(),
+            Err(_) => continue,
+        };
+
+        if header.quote_token_free == 0 {
+            continue;
+        }
+
+        let pending = Decimal::from_i128_with_scale(header.quote_token_free as i128, 9).round_dp(3);
+
+        output.push(vec![
+            item.market_address.to_string(),
+            item.token_title.clone(),
+            pending.to_string(),
+        ]);
+    }
+
+    Ok(output)
+}
+
+fn record_fixtures(
+    rpc: &RpcClient,
+    markets: &[MagicEdenItem],
+    owner: Option<&str>,
+    out: &Path,
+) -> Result<Output, FetchError> {
+    let owner = owner
+        .map(|owner| Pubkey::from_str(owner).map_err(|e| FetchError::InvalidInput(format!("owner address {owner}: {e}"))))
+        .transpose()?;
+
+    let items = markets.iter().collect::<Vec<_>>();
+    let accounts = rpc.load_market_accounts(&items)?;
+
+    let mut addresses = accounts
+        .iter()
+        .flat_map(|(_, accounts)| {
+            [accounts.market, accounts.orderbook, accounts.asks, accounts.bids, accounts.event_queue]
+        })
+        .map(|address| address.to_string())
+        .collect::<Vec<_>>();
+    if let Some(owner) = &owner {
+        addresses.extend(markets.iter().map(|item| item.user_account_address(owner).to_string()));
+    }
+
+    let recorded = addresses
+        .iter()
+        .cloned()
+        .zip(rpc.get_multiple_accounts_raw(&addresses)?)
+        .collect::<Map<_, _>>();
+
+    let prices = [KI_MINT, GENE_MINT]
+        .into_iter()
+        .map(|token| Ok((token.to_string(), Value::String(jupiter_price(token, SOL_MINT)?.to_string()))))
+        .collect::<Result<Map<_, _>, FetchError>>()?;
+    // The app does without the USD price, so the recording does too
+    let sol_usd = match jupiter_price(SOL_MINT, USDC_MINT) {
+        Ok(price) => Value::String(price.to_string()),
+        Err(e) => {
+            eprintln!("Recording without the SOL/USD price: {e}");
+            Value::Null
+        }
+    };
+    let usd_prices = Map::from_iter([(SOL_MINT.to_string(), sol_usd)]);
+
+    let base_vaults = accounts
+        .iter()
+        .map(|(_, accounts)| accounts.base_vault.to_string())
+        .collect::<Vec<_>>();
+    let trades = match flipside_trades()? {
+        Value::Array(trades) => trades
+            .into_iter()
+            .filter(|item| {
+                item.get("BASE_VAULT")
+                    .and_then(Value::as_str)
+                    .map_or(false, |base_vault| base_vaults.iter().any(|recorded| recorded == base_vault))
+            })
+            .collect(),
+        trades => trades,
+    };
+
+    let balances = match owner {
+        Some(owner) => {
+            let balances = rpc
+                .get_token_balances(&owner.to_string())?
+                .into_iter()
+                .map(|(mint, balance)| (mint, Value::String(balance.to_string())))
+                .collect::<Map<_, _>>();
+
+            Map::from_iter([(owner.to_string(), Value::Object(balances))])
+        }
+        None => Map::new(),
+    };
+
+    let files = [
+        ("accounts.json", Value::Object(recorded)),
+        ("prices.json", Value::Object(prices)),
+        ("usd_prices.json", Value::Object(usd_prices)),
+        ("trades.json", trades),
+        ("balances.json", Value::Object(balances)),
+    ];
+
+    fs::create_dir_all(out).map_err(|e| FetchError::Io(format!("{}: {e}", out.display())))?;
+    let mut output = Output::new(vec!["file", "bytes"]);
+
+    for (file, value) in files {
+        let path = out.join(file);
+        let json = serde_json::to_string(&value).map_err(|e| FetchError::Json(e.to_string()))?;
+
+        fs::write(&path, &json).map_err(|e| FetchError::Io(format!("{}: {e}", path.display())))?;
+        output.push(vec![path.display().to_string(), json.len().to_string()]);
+    }
+
+    Ok(output)
+}
+
+fn record_golden(rpc: &RpcClient, markets: &[MagicEdenItem], item: &str, out: &Path) -> Result<Output, FetchError> {
+    let item = markets
+        .iter()
+        .find(|market| {
+            market.token_address == item || market.market_address.to_string() == item || market.token_title == item
+        })
+        .ok_or_else(|| FetchError::Missing(format!("market matching {item}")))?;
+
+    let (_, accounts) = rpc
+        .load_market_accounts(&[item])?
+        .pop()
+        .ok_or_else(|| FetchError::Missing(format!("accounts of {}", item.market_address)))?;
+    let mut asks = rpc
+        .get_multiple_accounts(&[accounts.asks.to_string()])?
+        .pop()
+        .flatten()
+        .ok_or_else(|| FetchError::Missing(format!("asks account {}", accounts.asks)))?;
+
+    let data = base64::encode(&asks.data);
+    let listings = Listings::from_buffer(&mut asks.data, Side::Asks)
+        .map_err(|e| FetchError::SlabDecode {
+            address: accounts.asks.to_string(),
+            reason: e.to_string(),
+        })?
+        .map(|listing| {
+            serde_json::json!({
+                "key": listing.key.to_string(),
+                "owner": listing.owner.to_string(),
+                "price": listing.price,
+                "baseQuantity": listing.base_quantity,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let name = item.token_title.to_lowercase().replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+    let path = out.join(format!("mainnet_{name}.json"));
+    let golden = serde_json::json!({ "data": data, "listings": listings });
+    let json = serde_json::to_string_pretty(&golden).map_err(|e| FetchError::Json(e.to_string()))?;
+
+    fs::create_dir_all(out).map_err(|e| FetchError::Io(format!("{}: {e}", out.display())))?;
+    fs::write(&path, &json).map_err(|e| FetchError::Io(format!("{}: {e}", path.display())))?;
+
+    let mut output = Output::new(vec!["file", "listings"]);
+    output.push(vec![path.display().to_string(), listings.len().to_string()]);
+
+    Ok(output)
+}
+
+fn validate(recipes: Option<&Path>, markets: Option<&Path>) -> Result<Output, FetchError> {
+    fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, FetchError> {
+        let json = fs::read_to_string(path).map_err(|e| FetchError::Io(format!("{}: {e}", path.display())))?;
+
+        serde_json::from_str(&json).map_err(|e| FetchError::Json(format!("{}: {e}", path.display())))
+    }
+
+    let markets = match markets {
+        Some(path) => read_json::<Vec<MagicEdenItem>>(path)?,
+        None => genopets_markets(),
+    };
+    let items = markets.into_iter().map(|item| item.token_address).collect();
+
+    let recipes = match recipes {
+        Some(path) => read_json(path)?,
+        None => genopets_recipes_json(),
+    };
+
+    let issues = validate_recipes(&recipes, &items, &CURRENCIES);
+
+    let mut output = Output::new(vec!["kind", "issue"]);
+
+    for issue in issues {
+        output.push(vec![issue.kind().to_string(), issue.to_string()]);
+    }
+
+    Ok(output)
+}
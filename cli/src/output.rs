@@ -0,0 +1,115 @@
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+/// Rows of string cells under a fixed set of headers, printable in any of the
+/// supported formats
+pub struct Output {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Output {
+    pub fn new(headers: Vec<&'static str>) -> Self {
+        Self {
+            headers,
+            rows: vec![],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn push(&mut self, row: Vec<String>) {
+        debug_assert_eq!(row.len(), self.headers.len());
+
+        self.rows.push(row);
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Table => self.table(),
+            Format::Json => self.json(),
+            Format::Csv => self.csv(),
+        }
+    }
+
+    fn table(&self) -> String {
+        let widths = self
+            .headers
+            .iter()
+            .enumerate()
+            .map(|(i, header)| {
+                self.rows
+                    .iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([header.len()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+
+        let line = |cells: Vec<&str>| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        let separator = widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>();
+
+        let mut lines = vec![line(self.headers.clone())];
+        lines.push(line(separator.iter().map(String::as_str).collect()));
+        lines.extend(self.rows.iter().map(|row| line(row.iter().map(String::as_str).collect())));
+
+        lines.join("\n")
+    }
+
+    fn json(&self) -> String {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let object = self
+                    .headers
+                    .iter()
+                    .zip(row)
+                    .map(|(header, cell)| (header.to_string(), Value::String(cell.clone())))
+                    .collect::<Map<_, _>>();
+
+                Value::Object(object)
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&Value::Array(rows)).unwrap()
+    }
+
+    fn csv(&self) -> String {
+        fn escape(cell: &str) -> String {
+            match cell.contains([',', '"', '\n']) {
+                true => format!("\"{}\"", cell.replace('"', "\"\"")),
+                false => cell.to_string(),
+            }
+        }
+
+        let mut lines = vec![self.headers.iter().map(|header| escape(header)).collect::<Vec<_>>().join(",")];
+        lines.extend(
+            self.rows
+                .iter()
+                .map(|row| row.iter().map(|cell| escape(cell)).collect::<Vec<_>>().join(",")),
+        );
+
+        lines.join("\n")
+    }
+}
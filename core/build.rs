@@ -0,0 +1,46 @@
+//! Rejects recipe data with unknown reagents, bad amounts, cycles or items
+//! missing from the market registry, so they can't make it into a build
+
+#[allow(dead_code)]
+#[path = "src/validation.rs"]
+mod validation;
+
+use serde_json::Value;
+use std::collections::HashSet;
+
+const RECIPES_PATH: &str = "../collections/genopets_recipes.json";
+const MARKETS_PATH: &str = "../collections/genopets_sfts.json";
+
+fn main() {
+    println!("cargo:rerun-if-changed={RECIPES_PATH}");
+    println!("cargo:rerun-if-changed={MARKETS_PATH}");
+    println!("cargo:rerun-if-changed=src/validation.rs");
+
+    let recipes = read_json(RECIPES_PATH);
+    let items = match read_json(MARKETS_PATH) {
+        Value::Array(markets) => markets
+            .iter()
+            .filter_map(|item| item.get("tokenAddress")?.as_str())
+            .map(String::from)
+            .collect::<HashSet<_>>(),
+        _ => panic!("{MARKETS_PATH} should be an array of markets"),
+    };
+
+    let issues = validation::validate_recipes(&recipes, &items, &validation::CURRENCIES);
+
+    if !issues.is_empty() {
+        let issues = issues
+            .iter()
+            .map(|issue| format!("  {issue}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        panic!("{RECIPES_PATH} has issues:\n{issues}");
+    }
+}
+
+fn read_json(path: &str) -> Value {
+    let json = std::fs::read_to_string(path).unwrap_or_else(|e| panic!("Can't read {path}: {e}"));
+
+    serde_json::from_str(&json).unwrap_or_else(|e| panic!("{path} isn't valid JSON: {e}"))
+}
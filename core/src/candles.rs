@@ -0,0 +1,113 @@
+use crate::Trade;
+use chrono::{DateTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+
+const HOUR: i64 = 3_600;
+const DAY: i64 = 24 * HOUR;
+/// The epoch was a Thursday, weeks start on Monday
+const WEEK_OFFSET: i64 = 4 * DAY;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CandleInterval {
+    Hour,
+    FourHours,
+    Day,
+    Week,
+}
+
+impl CandleInterval {
+    pub const ALL: [CandleInterval; 4] = [
+        CandleInterval::Hour,
+        CandleInterval::FourHours,
+        CandleInterval::Day,
+        CandleInterval::Week,
+    ];
+
+    pub fn seconds(&self) -> i64 {
+        match self {
+            CandleInterval::Hour => HOUR,
+            CandleInterval::FourHours => 4 * HOUR,
+            CandleInterval::Day => DAY,
+            CandleInterval::Week => 7 * DAY,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CandleInterval::Hour => "1h",
+            CandleInterval::FourHours => "4h",
+            CandleInterval::Day => "1d",
+            CandleInterval::Week => "1w",
+        }
+    }
+
+    /// Start of the bucket the time falls in
+    pub fn bucket_start(&self, ts: DateTime<Utc>) -> DateTime<Utc> {
+        let offset = match self {
+            CandleInterval::Week => WEEK_OFFSET,
+            _ => 0,
+        };
+        let start = (ts.timestamp() - offset).div_euclid(self.seconds()) * self.seconds() + offset;
+
+        Utc.timestamp_opt(start, 0).unwrap()
+    }
+}
+
+/// Trades of one interval bucket
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candle {
+    pub start: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    /// Units traded
+    pub volume: Decimal,
+    /// Average price weighted by the units of each trade
+    pub vwap: Decimal,
+}
+
+/// Aggregates trades, in any order, into candles sorted by time. Buckets
+/// without trades are left out
+pub fn candles(trades: &[Trade], interval: CandleInterval) -> Vec<Candle> {
+    let mut trades = trades.iter().collect::<Vec<_>>();
+    // Stable, so trades at the same time keep their order
+    trades.sort_by_key(|trade| trade.ts);
+
+    let mut candles: Vec<Candle> = vec![];
+    // Sum of price times units of the last candle
+    let mut value = Decimal::ZERO;
+
+    for trade in trades {
+        let start = interval.bucket_start(trade.ts);
+
+        match candles.last_mut() {
+            Some(candle) if candle.start == start => {
+                candle.high = candle.high.max(trade.price);
+                candle.low = candle.low.min(trade.price);
+                candle.close = trade.price;
+                candle.volume += trade.amount;
+                value += trade.price * trade.amount;
+            }
+            _ => {
+                candles.push(Candle {
+                    start,
+                    open: trade.price,
+                    high: trade.price,
+                    low: trade.price,
+                    close: trade.price,
+                    volume: trade.amount,
+                    vwap: trade.price,
+                });
+                value = trade.price * trade.amount;
+            }
+        }
+
+        let candle = candles.last_mut().unwrap();
+        if !candle.volume.is_zero() {
+            candle.vwap = value / candle.volume;
+        }
+    }
+
+    candles
+}
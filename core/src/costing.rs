@@ -0,0 +1,279 @@
+use crate::{FeeModel, Ingredient, MagicEdenItem, MarketOrders, Recipes};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+
+/// Current market prices, all in SOL
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MarketPrices {
+    pub ki: Decimal,
+    pub gene: Decimal,
+    /// Ask floor of every listed item, by token address
+    pub floors: HashMap<String, FloorPrice>,
+}
+
+impl MarketPrices {
+    pub fn new(
+        (ki, gene): (Decimal, Decimal),
+        orders: &HashMap<String, MarketOrders>,
+        fees: impl Fn(&str) -> FeeModel,
+    ) -> Self {
+        let floors = orders
+            .iter()
+            .filter_map(|(token_address, orders)| {
+                let best_ask = orders.best_ask()?;
+                let fees = fees(token_address);
+
+                let floor = FloorPrice {
+                    buy: fees.buyer_pays(best_ask.price),
+                    sell: fees.raw_price(best_ask.price),
+                };
+
+                Some((token_address.clone(), floor))
+            })
+            .collect();
+
+        Self { ki, gene, floors }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FloorPrice {
+    /// What taking the cheapest ask costs, fees included
+    pub buy: Decimal,
+    /// What listing at the floor pays out once it fills
+    pub sell: Decimal,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Acquisition {
+    Craft,
+    Buy,
+}
+
+/// Prices items at the cheaper of crafting them or buying them at the floor,
+/// recursively for every reagent
+pub struct Costing<'a> {
+    recipes: &'a Recipes,
+    prices: &'a MarketPrices,
+    resolved: HashMap<String, Option<(Decimal, Acquisition)>>,
+    /// Items being priced further up the recipe tree, to cut cycles
+    visiting: HashSet<String>,
+    cycles_cut: usize,
+}
+
+impl<'a> Costing<'a> {
+    pub fn new(recipes: &'a Recipes, prices: &'a MarketPrices) -> Self {
+        Self {
+            recipes,
+            prices,
+            resolved: HashMap::new(),
+            visiting: HashSet::new(),
+            cycles_cut: 0,
+        }
+    }
+
+    /// Cost of crafting one unit, with each reagent crafted or bought,
+    /// whichever is cheaper. `None` if it can't be crafted, or one of its
+    /// reagents can be neither crafted nor bought
+    pub fn craft_cost(&mut self, token_address: &str) -> Option<Decimal> {
+        let ingredients = self.recipes.ingredients(token_address)?;
+
+        if !self.visiting.insert(token_address.to_string()) {
+            self.cycles_cut += 1;
+
+            return None;
+        }
+
+        let cost = ingredients
+            .iter()
+            .map(|(ingredient, amount)| {
+                let unit_cost = match ingredient {
+                    Ingredient::Ki => Some(self.prices.ki),
+                    Ingredient::Gene => Some(self.prices.gene),
+                    Ingredient::Item(reagent) => self.cheapest(reagent).map(|(cost, _)| cost),
+                };
+
+                unit_cost.map(|unit_cost| unit_cost * amount)
+            })
+            .sum();
+
+        self.visiting.remove(token_address);
+
+        cost
+    }
+
+    /// Cheapest way to get one unit of the item
+    pub fn cheapest(&mut self, token_address: &str) -> Option<(Decimal, Acquisition)> {
+        if let Some(cheapest) = self.resolved.get(token_address) {
+            return *cheapest;
+        }
+
+        let cycles_cut = self.cycles_cut;
+
+        let craft = self
+            .craft_cost(token_address)
+            .map(|cost| (cost, Acquisition::Craft));
+        let buy = self
+            .prices
+            .floors
+            .get(token_address)
+            .map(|floor| (floor.buy, Acquisition::Buy));
+
+        let cheapest = match (craft, buy) {
+            (Some(craft), Some(buy)) if buy.0 < craft.0 => Some(buy),
+            (Some(craft), _) => Some(craft),
+            (None, buy) => buy,
+        };
+
+        // Costs found while cutting a cycle depend on where the walk started
+        if self.cycles_cut == cycles_cut {
+            self.resolved.insert(token_address.to_string(), cheapest);
+        }
+
+        cheapest
+    }
+}
+
+impl<'a> Costing<'a> {
+    /// Recipe tree of one unit of the item, `None` if it can't be crafted
+    pub fn tree(&mut self, token_address: &str) -> Option<CostNode> {
+        self.recipes.ingredients(token_address)?;
+
+        let mut root = self.node(&Ingredient::Item(token_address.to_string()), Decimal::ONE);
+
+        // The tree shows what crafting the root takes, even when buying it is
+        // cheaper
+        root.ki = root.children.iter().map(|child| child.ki).sum();
+        root.gene = root.children.iter().map(|child| child.gene).sum();
+
+        Some(root)
+    }
+
+    fn node(&mut self, ingredient: &Ingredient, amount: Decimal) -> CostNode {
+        let (title, unit_price) = match ingredient {
+            Ingredient::Ki => (String::from("KI"), Some(self.prices.ki)),
+            Ingredient::Gene => (String::from("(s)GENE"), Some(self.prices.gene)),
+            Ingredient::Item(token_address) => (
+                self.recipes.title(token_address).unwrap_or(token_address).to_string(),
+                None,
+            ),
+        };
+
+        let mut node = CostNode {
+            ingredient: ingredient.clone(),
+            title,
+            amount,
+            ki: Decimal::ZERO,
+            gene: Decimal::ZERO,
+            sol: unit_price.map(|price| price * amount),
+            decision: None,
+            children: vec![],
+        };
+
+        let token_address = match ingredient {
+            Ingredient::Ki => {
+                node.ki = amount;
+                return node;
+            }
+            Ingredient::Gene => {
+                node.gene = amount;
+                return node;
+            }
+            Ingredient::Item(token_address) => token_address,
+        };
+
+        if let Some((cost, decision)) = self.cheapest(token_address) {
+            node.sol = Some(cost * amount);
+            node.decision = Some(decision);
+        }
+
+        // Children are listed even when buying is cheaper, to show what
+        // crafting would take
+        let ingredients = self.recipes.ingredients(token_address).unwrap_or_default();
+        if !self.visiting.insert(token_address.clone()) {
+            return node;
+        }
+
+        for (ingredient, child_amount) in ingredients {
+            let child = self.node(&ingredient, child_amount * amount);
+
+            node.ki += child.ki;
+            node.gene += child.gene;
+            node.children.push(child);
+        }
+
+        self.visiting.remove(token_address);
+
+        // Buying the item spends no KI or GENE at all
+        if node.decision == Some(Acquisition::Buy) {
+            node.ki = Decimal::ZERO;
+            node.gene = Decimal::ZERO;
+        }
+
+        node
+    }
+}
+
+/// One ingredient of a recipe tree, with the amount needed for the item at
+/// the root
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostNode {
+    pub ingredient: Ingredient,
+    pub title: String,
+    pub amount: Decimal,
+    /// KI spent crafting the subtree, counting only the items that are crafted
+    /// rather than bought. Bought items add none
+    pub ki: Decimal,
+    pub gene: Decimal,
+    /// Cost of the cheaper of crafting or buying, `None` when it can be
+    /// neither
+    pub sol: Option<Decimal>,
+    /// Only set for items
+    pub decision: Option<Acquisition>,
+    pub children: Vec<CostNode>,
+}
+
+/// An item that can be crafted for less than it lists for
+#[derive(Clone, Debug, PartialEq)]
+pub struct CraftOpportunity {
+    pub token_address: String,
+    pub token_title: String,
+    pub market_address: String,
+    pub production_cost: Decimal,
+    pub floor: Decimal,
+    pub margin_sol: Decimal,
+    pub margin_percent: Decimal,
+}
+
+/// Every listed item whose floor, after fees, pays more than crafting it costs
+pub fn craft_opportunities(
+    recipes: &Recipes,
+    markets: &[MagicEdenItem],
+    prices: &MarketPrices,
+) -> Vec<CraftOpportunity> {
+    let mut costing = Costing::new(recipes, prices);
+
+    markets
+        .iter()
+        .filter_map(|item| {
+            let floor = prices.floors.get(&item.token_address)?.sell;
+            let production_cost = costing.craft_cost(&item.token_address)?;
+
+            if floor <= production_cost || production_cost.is_zero() {
+                return None;
+            }
+
+            let margin_sol = floor - production_cost;
+
+            Some(CraftOpportunity {
+                token_address: item.token_address.clone(),
+                token_title: item.token_title.clone(),
+                market_address: item.market_address.to_string(),
+                production_cost,
+                floor,
+                margin_sol,
+                margin_percent: margin_sol / production_cost * Decimal::ONE_HUNDRED,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,47 @@
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FetchError {
+    /// The request couldn't be sent or the server answered with an error status
+    Http(String),
+    /// The server asked us to slow down, optionally telling for how long (in
+    /// seconds)
+    RateLimited { retry_after: Option<u32> },
+    /// The response body isn't what we expected
+    Json(String),
+    /// The RPC node answered with a JSON-RPC error object
+    JsonRpc { code: i64, message: String },
+    Base64(String),
+    SlabDecode { address: String, reason: String },
+    /// The response is valid but lacks the data we asked for
+    Missing(String),
+    /// An argument given by the user isn't valid
+    InvalidInput(String),
+    /// A local file couldn't be read or written
+    Io(String),
+}
+
+impl FetchError {
+    /// Whether sending the same request again later may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, FetchError::Http(_) | FetchError::RateLimited { .. })
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Http(reason) => write!(f, "HTTP error: {reason}"),
+            FetchError::RateLimited { .. } => write!(f, "Rate limited"),
+            FetchError::Json(reason) => write!(f, "Invalid response: {reason}"),
+            FetchError::JsonRpc { code, message } => write!(f, "RPC error {code}: {message}"),
+            FetchError::Base64(reason) => write!(f, "Invalid account data: {reason}"),
+            FetchError::SlabDecode { address, reason } => {
+                write!(f, "Couldn't decode orderbook {address}: {reason}")
+            }
+            FetchError::Missing(what) => write!(f, "Missing {what}"),
+            FetchError::InvalidInput(reason) => write!(f, "Invalid input: {reason}"),
+            FetchError::Io(reason) => write!(f, "I/O error: {reason}"),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use dex_v4::state::DexState;
+use rust_decimal::{Decimal, MathematicalOps};
+
+/// Taker fee charged by dex-v4 markets, in basis points
+const TAKER_FEE_BPS: u64 = 4;
+/// Taker fee charged by dex-v4 markets whose state has `fee_type == 1`
+/// (stable markets), in basis points. dex-v4 charges those a lower taker rate,
+/// this one isn't checked against a live listing
+const STABLE_TAKER_FEE_BPS: u64 = 1;
+/// Creator royalty of the Genopets markets, used until the market state is
+/// loaded. Loaded markets use the `royalties_bps` of their own state
+const DEFAULT_ROYALTIES_BPS: u64 = 500;
+/// Magic Eden's own fee on SFT trades, in basis points. Its marketplace
+/// doesn't charge anything on top of the dex-v4 market: the tracker used to
+/// match its displayed prices by dividing by a hand calibrated 0.9520, i.e.
+/// multiplying by 1.0504, which the 5% royalty and 0.04% taker fee already
+/// account for
+const MAGIC_EDEN_FEE_BPS: u64 = 0;
+const LAMPORTS_PER_SOL: i128 = 1_000_000_000;
+
+/// Converts the raw fixed-point orderbook prices into SOL prices, including
+/// the fees and royalties the market charges on top of them.
+///
+/// Listings quote the price the maker gets, so a buyer taking an ask pays the
+/// royalty, taker fee and Magic Eden's fee on top of it (5% + 0.04% + 0% = the
+/// 1.0504 multiplier Magic Eden displays), while a seller hitting a bid gets
+/// them deducted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FeeModel {
+    /// Creator royalty, from the market state
+    pub royalties_bps: u64,
+    pub taker_fee_bps: u64,
+    pub marketplace_fee_bps: u64,
+    pub base_currency_multiplier: u64,
+    pub quote_currency_multiplier: u64,
+}
+
+impl Default for FeeModel {
+    fn default() -> Self {
+        Self {
+            royalties_bps: DEFAULT_ROYALTIES_BPS,
+            taker_fee_bps: TAKER_FEE_BPS,
+            marketplace_fee_bps: MAGIC_EDEN_FEE_BPS,
+            base_currency_multiplier: 1,
+            quote_currency_multiplier: 1_000_000,
+        }
+    }
+}
+
+impl FeeModel {
+    pub fn from_dex_state(dex_state: &DexState) -> Self {
+        let taker_fee_bps = match dex_state.fee_type {
+            1 => STABLE_TAKER_FEE_BPS,
+            _ => TAKER_FEE_BPS,
+        };
+
+        Self {
+            royalties_bps: dex_state.royalties_bps,
+            taker_fee_bps,
+            marketplace_fee_bps: MAGIC_EDEN_FEE_BPS,
+            base_currency_multiplier: dex_state.base_currency_multiplier,
+            quote_currency_multiplier: dex_state.quote_currency_multiplier,
+        }
+    }
+
+    /// SOL price per unit, without any fees
+    pub fn raw_price(&self, price: u64) -> Decimal {
+        let price = Decimal::from_i128_with_scale(price as i128, 0);
+        let quote_currency_multiplier = Decimal::from(self.quote_currency_multiplier);
+        let base_currency_multiplier = Decimal::from(self.base_currency_multiplier.max(1));
+
+        let numerator = price * quote_currency_multiplier;
+        let denominator = Decimal::from_i128_with_scale(LAMPORTS_PER_SOL, 0)
+            * Decimal::TWO.powu(32)
+            * base_currency_multiplier;
+
+        numerator / denominator
+    }
+
+    /// Units of the SFT in an amount of base lots
+    pub fn base_amount(&self, base_size: u64) -> Decimal {
+        Decimal::from(base_size) * Decimal::from(self.base_currency_multiplier.max(1))
+    }
+
+    /// SOL price per unit of a fill of base and quote lots, without any fees.
+    /// `None` if nothing was filled
+    pub fn fill_price(&self, base_size: u64, quote_size: u64) -> Option<Decimal> {
+        let lamports = Decimal::from(quote_size) * Decimal::from(self.quote_currency_multiplier);
+        let sol = lamports / Decimal::from_i128_with_scale(LAMPORTS_PER_SOL, 0);
+
+        sol.checked_div(self.base_amount(base_size))
+    }
+
+    /// SOL price per unit paid by a buyer taking an ask
+    pub fn buyer_pays(&self, price: u64) -> Decimal {
+        self.raw_price(price) * (Decimal::ONE + self.fee_rate())
+    }
+
+    /// SOL price per unit received by a seller hitting a bid
+    pub fn seller_receives(&self, price: u64) -> Decimal {
+        self.raw_price(price) * (Decimal::ONE - self.fee_rate())
+    }
+
+    fn fee_rate(&self) -> Decimal {
+        let fee_bps = self.royalties_bps + self.taker_fee_bps + self.marketplace_fee_bps;
+
+        Decimal::from_i128_with_scale(fee_bps as i128, 4)
+    }
+}
+
@@ -0,0 +1,38 @@
+//! Platform-agnostic pieces of the Magic Eden SFT tracker: the market
+//! registry, orderbook decoding, recipe costing, price math and the RPC types
+//! needed to load all of it.
+
+pub mod candles;
+pub mod costing;
+pub mod error;
+pub mod fees;
+pub mod listings;
+pub mod market;
+pub mod market_sort;
+pub mod overview;
+pub mod planner;
+pub mod prices;
+pub mod recipes;
+pub mod registry;
+pub mod rpc;
+pub mod subscriptions;
+pub mod sweep;
+pub mod trade_stats;
+pub mod trades;
+pub mod validation;
+
+pub use self::candles::*;
+pub use self::costing::*;
+pub use self::error::*;
+pub use self::fees::*;
+pub use self::listings::*;
+pub use self::market::*;
+pub use self::market_sort::*;
+pub use self::overview::*;
+pub use self::planner::*;
+pub use self::recipes::*;
+pub use self::registry::*;
+pub use self::subscriptions::*;
+pub use self::sweep::*;
+pub use self::trade_stats::*;
+pub use self::trades::*;
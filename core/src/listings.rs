@@ -0,0 +1,271 @@
+use asset_agnostic_orderbook::state::{
+    critbit::{Node, Slab},
+    AccountTag,
+};
+use crate::FeeModel;
+use dex_v4::state::CallBackInfo;
+use rust_decimal::Decimal;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Bids,
+    Asks,
+}
+
+impl From<Side> for AccountTag {
+    fn from(side: Side) -> Self {
+        match side {
+            Side::Bids => AccountTag::Bids,
+            Side::Asks => AccountTag::Asks,
+        }
+    }
+}
+
+/// Walks every leaf of one side of the orderbook. Leaves are yielded in critbit
+/// key order, which for both sides means ascending price.
+pub struct Listings<'a> {
+    slab: Slab<'a, CallBackInfo>,
+    search_stack: Vec<u32>,
+}
+
+impl<'a> Listings<'a> {
+    pub fn from_buffer(buf: &'a mut [u8], side: Side) -> Result<Self, ProgramError> {
+        let slab = Slab::from_buffer(buf, side.into())?;
+
+        Ok(Self {
+            search_stack: match slab.root() {
+                Some(root_node) => vec![root_node],
+                None => vec![],
+            },
+            slab,
+        })
+    }
+
+    pub fn to_vec(self) -> Vec<Listing> {
+        self.collect()
+    }
+}
+
+impl<'a> Iterator for Listings<'a> {
+    type Item = Listing;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(current) = self.search_stack.pop() {
+            match Node::from_handle(current) {
+                Node::Inner => {
+                    let n = &self.slab.inner_nodes[(!current) as usize];
+                    self.search_stack.push(n.children[1]);
+                    self.search_stack.push(n.children[0]);
+                }
+                Node::Leaf => {
+                    let leaf = self.slab.leaf_nodes[current as usize];
+
+                    return Some(Listing {
+                        key: leaf.key,
+                        owner: self.slab.callback_infos[current as usize].user_account,
+                        price: leaf.price(),
+                        base_quantity: leaf.base_quantity,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Listing {
+    pub key: u128,
+    pub owner: Pubkey,
+    pub price: u64,
+    pub base_quantity: u64,
+}
+
+/// Both sides of a market's orderbook, each sorted by ascending price
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MarketOrders {
+    pub bids: Vec<Listing>,
+    pub asks: Vec<Listing>,
+}
+
+impl MarketOrders {
+    pub fn best_bid(&self) -> Option<&Listing> {
+        self.bids.iter().max_by_key(|listing| listing.price)
+    }
+
+    pub fn best_ask(&self) -> Option<&Listing> {
+        self.asks.iter().min_by_key(|listing| listing.price)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Listing> + Clone {
+        self.bids.iter().chain(self.asks.iter())
+    }
+
+    pub fn side_mut(&mut self, side: Side) -> &mut Vec<Listing> {
+        match side {
+            Side::Bids => &mut self.bids,
+            Side::Asks => &mut self.asks,
+        }
+    }
+
+    /// Both sides as updates of the given market
+    pub fn into_updates(self, token_address: String) -> [SideUpdate; 2] {
+        [
+            SideUpdate {
+                token_address: token_address.clone(),
+                side: Side::Bids,
+                listings: self.bids,
+            },
+            SideUpdate {
+                token_address,
+                side: Side::Asks,
+                listings: self.asks,
+            },
+        ]
+    }
+
+    /// Orders of one side, best price first. Orders at the same price keep
+    /// their orderbook order
+    pub fn sorted(&self, side: Side) -> Vec<Listing> {
+        let mut listings = match side {
+            Side::Bids => self.bids.clone(),
+            Side::Asks => self.asks.clone(),
+        };
+
+        match side {
+            Side::Bids => listings.sort_by(|a, b| b.price.cmp(&a.price)),
+            Side::Asks => listings.sort_by_key(|listing| listing.price),
+        }
+
+        listings
+    }
+
+    /// Aggregates one side into price levels, best price first. The cumulative
+    /// SOL is what taking every level up to and including this one would cost
+    /// (asks) or pay out (bids), fees included
+    pub fn price_levels(&self, side: Side, fees: &FeeModel) -> Vec<PriceLevel> {
+        let mut levels: Vec<PriceLevel> = vec![];
+
+        for listing in self.sorted(side) {
+            match levels.last_mut() {
+                Some(level) if level.price == listing.price => {
+                    level.base_quantity += listing.base_quantity;
+                    level.order_count += 1;
+                }
+                _ => levels.push(PriceLevel {
+                    price: listing.price,
+                    base_quantity: listing.base_quantity,
+                    order_count: 1,
+                    cumulative_quantity: 0,
+                    cumulative_sol: Decimal::ZERO,
+                }),
+            }
+        }
+
+        let mut cumulative_quantity = 0;
+        let mut cumulative_sol = Decimal::ZERO;
+
+        for level in &mut levels {
+            let ui_price = match side {
+                Side::Bids => fees.seller_receives(level.price),
+                Side::Asks => fees.buyer_pays(level.price),
+            };
+
+            cumulative_quantity += level.base_quantity;
+            cumulative_sol += ui_price * Decimal::from(level.base_quantity);
+
+            level.cumulative_quantity = cumulative_quantity;
+            level.cumulative_sol = cumulative_sol;
+        }
+
+        levels
+    }
+}
+
+/// New orders of one side of a market, the other side is left as it is
+#[derive(Clone, Debug, PartialEq)]
+pub struct SideUpdate {
+    pub token_address: String,
+    pub side: Side,
+    pub listings: Vec<Listing>,
+}
+
+/// Every order of one side at the same price
+#[derive(Clone, Debug, PartialEq)]
+pub struct PriceLevel {
+    /// Raw orderbook price
+    pub price: u64,
+    pub base_quantity: u64,
+    pub order_count: usize,
+    /// Quantity of this level and every better one
+    pub cumulative_quantity: u64,
+    pub cumulative_sol: Decimal,
+}
+
+impl MarketOrders {
+    /// Compares both sides against a previous snapshot of the same market.
+    /// Orders are matched by key, and since the key includes the price, an
+    /// order replaced by the same owner at a different price counts as
+    /// repriced rather than new
+    pub fn diff(&self, previous: &MarketOrders) -> OrderChanges {
+        let mut changes = OrderChanges::default();
+
+        let sides = [
+            (&self.bids, &previous.bids, &mut changes.removed.bids),
+            (&self.asks, &previous.asks, &mut changes.removed.asks),
+        ];
+
+        for (current, previous, removed) in sides {
+            let previous_by_key = previous
+                .iter()
+                .map(|listing| (listing.key, listing))
+                .collect::<HashMap<_, _>>();
+            let current_keys = current
+                .iter()
+                .map(|listing| listing.key)
+                .collect::<HashSet<_>>();
+
+            removed.extend(
+                previous
+                    .iter()
+                    .filter(|listing| !current_keys.contains(&listing.key))
+                    .cloned(),
+            );
+
+            for listing in current {
+                let change = match previous_by_key.get(&listing.key) {
+                    Some(old) if old.base_quantity != listing.base_quantity => OrderChange::Resized,
+                    Some(_) => continue,
+                    None if removed.iter().any(|old| old.owner == listing.owner) => OrderChange::Repriced,
+                    None => OrderChange::New,
+                };
+
+                changes.changed.insert(listing.key, change);
+            }
+        }
+
+        changes
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderChange {
+    New,
+    Repriced,
+    Resized,
+}
+
+#[derive(Clone, Default, PartialEq)]
+pub struct OrderChanges {
+    pub changed: HashMap<u128, OrderChange>,
+    pub removed: MarketOrders,
+}
+
+impl OrderChanges {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.bids.is_empty() && self.removed.asks.is_empty()
+    }
+}
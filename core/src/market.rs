@@ -0,0 +1,79 @@
+use crate::{FeeModel, MagicEdenItem};
+use asset_agnostic_orderbook::state::market_state::MarketState as OrderbookState;
+use dex_v4::state::DexState;
+use solana_program::pubkey::Pubkey;
+use std::mem::size_of;
+
+/// Every account of a dex-v4 market, plus its fee parameters, as derived from
+/// chain state
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarketAccounts {
+    pub market: Pubkey,
+    pub orderbook: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub event_queue: Pubkey,
+    pub base_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub fees: FeeModel,
+}
+
+impl MarketAccounts {
+    pub fn new(market: Pubkey, dex_state: &DexState, orderbook_state: &OrderbookState) -> Self {
+        Self {
+            market,
+            orderbook: dex_state.orderbook,
+            bids: Pubkey::new_from_array(orderbook_state.bids),
+            asks: Pubkey::new_from_array(orderbook_state.asks),
+            event_queue: Pubkey::new_from_array(orderbook_state.event_queue),
+            base_mint: dex_state.base_mint,
+            base_vault: dex_state.base_vault,
+            quote_vault: dex_state.quote_vault,
+            fees: FeeModel::from_dex_state(dex_state),
+        }
+    }
+
+    /// Compares the addresses declared in the static market list against the
+    /// ones found on chain
+    pub fn mismatches(&self, item: &MagicEdenItem) -> Vec<MarketMismatch> {
+        let checks = [
+            ("tokenAddress", Some(&item.token_address), &self.base_mint),
+            ("asksAddress", item.asks_address.as_ref(), &self.asks),
+            ("baseVaultAddress", item.base_vault_address.as_ref(), &self.base_vault),
+        ];
+
+        checks
+            .into_iter()
+            .filter_map(|(field, declared, actual)| {
+                let declared = declared?;
+
+                (declared != &actual.to_string()).then(|| MarketMismatch {
+                    field,
+                    declared: declared.clone(),
+                    actual: *actual,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarketMismatch {
+    pub field: &'static str,
+    pub declared: String,
+    pub actual: Pubkey,
+}
+
+pub fn decode_dex_state(buf: &[u8]) -> Option<&DexState> {
+    let state: &DexState = bytemuck::try_from_bytes(buf.get(..size_of::<DexState>())?).ok()?;
+
+    (state.tag == dex_v4::state::AccountTag::DexState as u64).then_some(state)
+}
+
+pub fn decode_orderbook_state(buf: &[u8]) -> Option<&OrderbookState> {
+    let state: &OrderbookState =
+        bytemuck::try_from_bytes(buf.get(..size_of::<OrderbookState>())?).ok()?;
+
+    (state.tag == asset_agnostic_orderbook::state::AccountTag::Market as u64).then_some(state)
+}
@@ -0,0 +1,162 @@
+use crate::{FeeModel, MagicEdenItem, MarketOrders, Trade, TradeWindow, WindowStats};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SortColumn {
+    #[default]
+    Name,
+    Floor,
+    Listed,
+    Sellers,
+    Volume,
+    AvgPrice,
+    ProductionCost,
+    CraftMargin,
+}
+
+impl SortColumn {
+    pub const ALL: [SortColumn; 8] = [
+        SortColumn::Name,
+        SortColumn::Floor,
+        SortColumn::Listed,
+        SortColumn::Sellers,
+        SortColumn::Volume,
+        SortColumn::AvgPrice,
+        SortColumn::ProductionCost,
+        SortColumn::CraftMargin,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortColumn::Name => "Name",
+            SortColumn::Floor => "Floor",
+            SortColumn::Listed => "Listed quantity",
+            SortColumn::Sellers => "Sellers",
+            SortColumn::Volume => "30d volume",
+            SortColumn::AvgPrice => "Average trade price",
+            SortColumn::ProductionCost => "Production cost",
+            SortColumn::CraftMargin => "Craft margin",
+        }
+    }
+
+    /// Stable name, for select values
+    pub fn key(&self) -> &'static str {
+        match self {
+            SortColumn::Name => "name",
+            SortColumn::Floor => "floor",
+            SortColumn::Listed => "listed",
+            SortColumn::Sellers => "sellers",
+            SortColumn::Volume => "volume",
+            SortColumn::AvgPrice => "avg_price",
+            SortColumn::ProductionCost => "production_cost",
+            SortColumn::CraftMargin => "craft_margin",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|column| column.key() == key)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MarketSort {
+    pub column: SortColumn,
+    pub descending: bool,
+}
+
+impl MarketSort {
+    /// Sorts by the column, keeping markets missing the figure last in
+    /// either direction. Ties keep their registry order
+    pub fn sort<T>(&self, rows: &mut [(T, MarketMetrics)]) {
+        rows.sort_by(|(_, a), (_, b)| {
+            let ordering = match self.column {
+                SortColumn::Name => Some(a.name.to_lowercase().cmp(&b.name.to_lowercase())),
+                SortColumn::Floor => compare(a.floor, b.floor),
+                SortColumn::Listed => Some(a.listed.cmp(&b.listed)),
+                SortColumn::Sellers => Some(a.sellers.cmp(&b.sellers)),
+                SortColumn::Volume => Some(a.volume.cmp(&b.volume)),
+                SortColumn::AvgPrice => compare(a.avg_price, b.avg_price),
+                SortColumn::ProductionCost => compare(a.production_cost, b.production_cost),
+                SortColumn::CraftMargin => compare(a.craft_margin(), b.craft_margin()),
+            };
+
+            match ordering {
+                Some(ordering) if self.descending => ordering.reverse(),
+                Some(ordering) => ordering,
+                None => missing_last(a, b, self.column),
+            }
+        });
+    }
+}
+
+/// Compares present figures, `None` if either is missing
+fn compare(a: Option<Decimal>, b: Option<Decimal>) -> Option<Ordering> {
+    Some(a?.cmp(&b?))
+}
+
+fn missing_last(a: &MarketMetrics, b: &MarketMetrics, column: SortColumn) -> Ordering {
+    let present = |metrics: &MarketMetrics| match column {
+        SortColumn::Floor => metrics.floor.is_some(),
+        SortColumn::AvgPrice => metrics.avg_price.is_some(),
+        SortColumn::ProductionCost => metrics.production_cost.is_some(),
+        SortColumn::CraftMargin => metrics.craft_margin().is_some(),
+        _ => true,
+    };
+
+    present(b).cmp(&present(a))
+}
+
+/// Figures of a market the table can be sorted by. Prices are in SOL
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MarketMetrics {
+    pub name: String,
+    /// Cheapest ask, fees included
+    pub floor: Option<Decimal>,
+    /// What listing at the floor pays out once it fills
+    pub floor_proceeds: Option<Decimal>,
+    /// Units listed
+    pub listed: u64,
+    /// Distinct owners of the asks
+    pub sellers: usize,
+    /// Units traded in the last 30 days
+    pub volume: Decimal,
+    /// VWAP of the last 30 days
+    pub avg_price: Option<Decimal>,
+    pub production_cost: Option<Decimal>,
+}
+
+impl MarketMetrics {
+    pub fn new(
+        item: &MagicEdenItem,
+        orders: &MarketOrders,
+        fees: &FeeModel,
+        trades: Option<&[Trade]>,
+        production_cost: Option<Decimal>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let best_ask = orders.best_ask();
+        let stats = trades
+            .map(|trades| WindowStats::new(trades, TradeWindow::Month, now))
+            .unwrap_or_default();
+
+        Self {
+            name: item.token_title.clone(),
+            floor: best_ask.map(|listing| fees.buyer_pays(listing.price)),
+            floor_proceeds: best_ask.map(|listing| fees.raw_price(listing.price)),
+            listed: orders.asks.iter().map(|listing| listing.base_quantity).sum(),
+            sellers: orders.asks.iter().map(|listing| listing.owner).collect::<HashSet<_>>().len(),
+            volume: stats.volume,
+            avg_price: stats.vwap,
+            production_cost,
+        }
+    }
+
+    /// What selling a crafted unit at the floor makes over crafting it
+    pub fn craft_margin(&self) -> Option<Decimal> {
+        Some(self.floor_proceeds? - self.production_cost?)
+    }
+}
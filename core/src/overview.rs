@@ -0,0 +1,106 @@
+use crate::trade_stats::median;
+use crate::{FeeModel, MagicEdenItem, MarketOrders, Trade, TradeWindow, WindowStats};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use solana_program::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashMap};
+
+/// Movers listed per collection
+const MOVERS: usize = 3;
+
+/// Market figures of every item of a collection together
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollectionOverview {
+    pub collection: String,
+    /// Items with a loaded orderbook
+    pub items: usize,
+    pub listed_units: u64,
+    /// Cheapest ask of any item, fees included
+    pub floor: Option<Decimal>,
+    /// Median price of the asks of every item, fees included
+    pub median_ask: Option<Decimal>,
+    /// SOL traded
+    pub volume_24h: Decimal,
+    pub volume_7d: Decimal,
+    /// Items whose 24h VWAP moved the most against the day before, biggest
+    /// move first
+    pub movers: Vec<Mover>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mover {
+    pub token_address: String,
+    pub token_title: String,
+    pub market_address: Pubkey,
+    pub change_percent: Decimal,
+}
+
+/// Aggregates the markets by collection, sorted by collection. Trades are by
+/// token address
+pub fn collection_overviews(
+    markets: &[MagicEdenItem],
+    orders: &HashMap<String, MarketOrders>,
+    trades: &HashMap<String, Vec<Trade>>,
+    fees: impl Fn(&str) -> FeeModel,
+    now: DateTime<Utc>,
+) -> Vec<CollectionOverview> {
+    let mut collections = BTreeMap::<&str, Vec<&MagicEdenItem>>::new();
+    for item in markets {
+        collections.entry(item.collection.as_str()).or_default().push(item);
+    }
+
+    collections
+        .into_iter()
+        .map(|(collection, items)| {
+            let mut asks = vec![];
+            let mut listed_units = 0;
+            let mut volume_24h = Decimal::ZERO;
+            let mut volume_7d = Decimal::ZERO;
+            let mut movers = vec![];
+            let mut loaded = 0;
+
+            for item in items {
+                if let Some(orders) = orders.get(&item.token_address) {
+                    let item_fees = fees(&item.token_address);
+
+                    loaded += 1;
+                    listed_units += orders.asks.iter().map(|listing| listing.base_quantity).sum::<u64>();
+                    asks.extend(orders.asks.iter().map(|listing| item_fees.buyer_pays(listing.price)));
+                }
+
+                let item_trades = match trades.get(&item.token_address) {
+                    Some(item_trades) => item_trades,
+                    None => continue,
+                };
+
+                let day = WindowStats::new(item_trades, TradeWindow::Day, now);
+                volume_24h += day.sol_volume;
+                volume_7d += WindowStats::new(item_trades, TradeWindow::Week, now).sol_volume;
+
+                if let Some(change_percent) = day.change_percent {
+                    movers.push(Mover {
+                        token_address: item.token_address.clone(),
+                        token_title: item.token_title.clone(),
+                        market_address: item.market_address,
+                        change_percent,
+                    });
+                }
+            }
+
+            asks.sort();
+            movers.sort_by(|a, b| b.change_percent.abs().cmp(&a.change_percent.abs()));
+            movers.truncate(MOVERS);
+
+            CollectionOverview {
+                collection: collection.to_string(),
+                items: loaded,
+                listed_units,
+                floor: asks.first().copied(),
+                median_ask: median(&asks),
+                volume_24h,
+                volume_7d,
+                movers,
+            }
+        })
+        .collect()
+}
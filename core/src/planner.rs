@@ -0,0 +1,161 @@
+use crate::prices::{GENE_MINT, KI_MINT};
+use crate::{FeeModel, Ingredient, MarketOrders, Recipes, SweepQuote};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Inputs needed to craft a batch of items, after using up what the owner
+/// already holds
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CraftPlan {
+    pub ki: PlanLine,
+    pub gene: PlanLine,
+    /// Every SFT involved, targets included, by token address
+    pub items: BTreeMap<String, PlanLine>,
+    /// Items whose recipe needs the item itself further down, by token
+    /// address. They're bought rather than crafted
+    pub cycles: BTreeSet<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlanLine {
+    pub needed: Decimal,
+    /// Taken from the owner's holdings
+    pub held: Decimal,
+    /// Crafted from its own recipe, whose ingredients are part of the plan
+    pub crafted: Decimal,
+    /// Left to buy
+    pub to_buy: Decimal,
+}
+
+impl CraftPlan {
+    /// Expands the targets through their recipes. Holdings are used first at
+    /// every level below the targets, what's missing is crafted when there's a
+    /// recipe and bought otherwise. Items with a base cost (crystals) are
+    /// bought rather than refined, so they show up in the shopping list.
+    ///
+    /// Holdings are token balances by mint, KI and GENE included.
+    pub fn new(recipes: &Recipes, targets: &[(String, Decimal)], holdings: &HashMap<String, Decimal>) -> Self {
+        let mut plan = CraftPlan::default();
+        let mut available = holdings.clone();
+
+        for (token_address, quantity) in targets {
+            plan.expand(recipes, token_address, *quantity, &mut available, &mut vec![]);
+        }
+
+        for (line, mint) in [(&mut plan.ki, KI_MINT), (&mut plan.gene, GENE_MINT)] {
+            line.held = available.get(mint).copied().unwrap_or_default().min(line.needed);
+            line.to_buy = line.needed - line.held;
+        }
+
+        plan
+    }
+
+    fn expand(
+        &mut self,
+        recipes: &Recipes,
+        token_address: &str,
+        quantity: Decimal,
+        available: &mut HashMap<String, Decimal>,
+        visiting: &mut Vec<String>,
+    ) {
+        let line = self.items.entry(token_address.to_string()).or_default();
+        line.needed += quantity;
+
+        // Targets are what we want to end up with, so they don't come out of
+        // the holdings
+        let held = match visiting.is_empty() {
+            true => Decimal::ZERO,
+            false => available.get(token_address).copied().unwrap_or_default().min(quantity),
+        };
+        if held > Decimal::ZERO {
+            line.held += held;
+            *available.entry(token_address.to_string()).or_default() -= held;
+        }
+
+        let missing = quantity - held;
+        if missing.is_zero() {
+            return;
+        }
+
+        let ingredients = match recipes.recipe(token_address) {
+            // Crafting it would take the item itself further down
+            Some(_) if visiting.iter().any(|item| item == token_address) => {
+                line.to_buy += missing;
+                self.cycles.insert(token_address.to_string());
+                return;
+            }
+            Some(ingredients) => ingredients,
+            None => {
+                line.to_buy += missing;
+                return;
+            }
+        };
+        line.crafted += missing;
+
+        visiting.push(token_address.to_string());
+        for (ingredient, amount) in ingredients {
+            match ingredient {
+                Ingredient::Ki => self.ki.needed += amount * missing,
+                Ingredient::Gene => self.gene.needed += amount * missing,
+                Ingredient::Item(reagent) => self.expand(recipes, &reagent, amount * missing, available, visiting),
+            }
+        }
+        visiting.pop();
+    }
+
+    /// Prices what's left to buy, sweeping the asks of each item
+    pub fn shopping_cost(
+        &self,
+        orders: &HashMap<String, MarketOrders>,
+        fees: impl Fn(&str) -> FeeModel,
+        (ki_price, gene_price): (Decimal, Decimal),
+    ) -> ShoppingCost {
+        let items = self
+            .items
+            .iter()
+            .filter(|(_, line)| line.to_buy > Decimal::ZERO)
+            .map(|(token_address, line)| {
+                // Orders are for whole units
+                let quantity = line.to_buy.ceil().to_u64().unwrap_or(u64::MAX);
+                let quote = orders
+                    .get(token_address)
+                    .map(|orders| orders.sweep(quantity, &fees(token_address)));
+
+                (token_address.clone(), quote)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let ki_sol = self.ki.to_buy * ki_price;
+        let gene_sol = self.gene.to_buy * gene_price;
+        let total_sol = ki_sol
+            + gene_sol
+            + items
+                .values()
+                .flatten()
+                .map(|quote| quote.total_sol)
+                .sum::<Decimal>();
+        let complete = items
+            .values()
+            .all(|quote| quote.as_ref().map_or(false, SweepQuote::has_enough_depth));
+
+        ShoppingCost {
+            ki_sol,
+            gene_sol,
+            items,
+            total_sol,
+            complete,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShoppingCost {
+    pub ki_sol: Decimal,
+    pub gene_sol: Decimal,
+    /// `None` for items whose orderbook isn't loaded
+    pub items: BTreeMap<String, Option<SweepQuote>>,
+    pub total_sol: Decimal,
+    /// Whether the asks cover everything, otherwise the total is short
+    pub complete: bool,
+}
@@ -0,0 +1,79 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const KI_MINT: &str = "kiGenopAScF8VF31Zbtx2Hg8qA5ArGqvnVtXb83sotc";
+pub const GENE_MINT: &str = "GENEtH5amGSi8kHAtQoezp1XEXwZJ8vcuePYnXdKrMYz";
+pub const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+/// Jupiter price API URL quoting the token in another one, usually SOL
+pub fn jupiter_price_url(token: &str, vs_token: &str) -> String {
+    format!("https://price.jup.ag/v3/price?ids={token}&vsToken={vs_token}")
+}
+
+#[derive(Deserialize)]
+pub struct JupiterPrice {
+    pub data: HashMap<String, JupiterPriceData>,
+}
+
+#[derive(Deserialize)]
+pub struct JupiterPriceData {
+    pub price: Decimal,
+}
+
+/// Currency prices are shown in. Everything is priced in SOL on chain, the
+/// rest are conversions
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Currency {
+    #[default]
+    Sol,
+    Usd,
+    Ki,
+    Gene,
+}
+
+impl Currency {
+    pub const ALL: [Currency; 4] = [Currency::Sol, Currency::Usd, Currency::Ki, Currency::Gene];
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::Sol => "SOL",
+            Currency::Usd => "USD",
+            Currency::Ki => "KI",
+            Currency::Gene => "GENE",
+        }
+    }
+
+    pub fn from_symbol(symbol: &str) -> Option<Currency> {
+        Currency::ALL.into_iter().find(|currency| currency.symbol() == symbol)
+    }
+}
+
+/// Prices of KI and GENE in SOL, and of SOL in USD
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExchangeRates {
+    pub ki: Decimal,
+    pub gene: Decimal,
+    /// `None` when the USD quote couldn't be loaded, which only rules out
+    /// showing prices in USD
+    pub sol_usd: Option<Decimal>,
+}
+
+impl ExchangeRates {
+    /// KI and GENE prices in SOL, as used for costing
+    pub fn tokens(&self) -> (Decimal, Decimal) {
+        (self.ki, self.gene)
+    }
+
+    /// Converts an amount of SOL. `None` if the token price is zero, or the
+    /// USD price is missing
+    pub fn convert(&self, sol: Decimal, currency: Currency) -> Option<Decimal> {
+        match currency {
+            Currency::Sol => Some(sol),
+            Currency::Usd => self.sol_usd.map(|sol_usd| sol * sol_usd),
+            Currency::Ki => sol.checked_div(self.ki),
+            Currency::Gene => sol.checked_div(self.gene),
+        }
+    }
+}
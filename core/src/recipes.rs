@@ -0,0 +1,182 @@
+use crate::prices::KI_MINT;
+use crate::MagicEdenItem;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+const GENOPETS_RECIPES: &str = include_str!("../../collections/genopets_recipes.json");
+pub use crate::validation::CURRENCIES;
+
+/// Ingredients by token address (or KI mint, or `GENE`) and amount
+pub type RecipeIngredients = HashMap<String, Decimal>;
+
+/// Contents of the recipes data file, see
+/// `collections/genopets_recipes.schema.json`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeData {
+    /// Fixed costs of items that aren't crafted from other SFTs
+    pub base_costs: HashMap<String, RecipeIngredients>,
+    pub recipes: HashMap<String, RecipeIngredients>,
+}
+
+impl RecipeData {
+    pub fn genopets() -> Self {
+        serde_json::from_str(GENOPETS_RECIPES).unwrap()
+    }
+}
+
+/// The bundled recipes file, as is, for validation
+pub fn genopets_recipes_json() -> Value {
+    serde_json::from_str(GENOPETS_RECIPES).unwrap()
+}
+
+pub struct Recipes {
+    data: RecipeData,
+    markets: Vec<MagicEdenItem>,
+}
+
+impl Recipes {
+    pub fn new(markets: Vec<MagicEdenItem>) -> Recipes {
+        Self::from_data(RecipeData::genopets(), markets)
+    }
+
+    pub fn from_data(data: RecipeData, markets: Vec<MagicEdenItem>) -> Recipes {
+        Self { data, markets }
+    }
+
+    pub fn title(&self, token_address: &str) -> Option<&str> {
+        self.markets
+            .iter()
+            .find(|item| item.token_address == token_address)
+            .map(|item| item.token_title.as_str())
+    }
+
+    /// Direct ingredients of one unit of the item, from its base cost or else
+    /// its recipe. `None` if it has neither and can only be bought
+    pub fn ingredients(&self, token_address: &str) -> Option<Vec<(Ingredient, Decimal)>> {
+        let ingredients = self
+            .data
+            .base_costs
+            .get(token_address)
+            .or_else(|| self.data.recipes.get(token_address))?;
+
+        Some(to_ingredients(ingredients))
+    }
+
+    /// Direct ingredients of one unit of the item when crafting it, ignoring
+    /// base costs
+    pub fn recipe(&self, token_address: &str) -> Option<Vec<(Ingredient, Decimal)>> {
+        self.data.recipes.get(token_address).map(to_ingredients)
+    }
+
+    /// KI and GENE needed to make one unit of the item, crafting every reagent
+    /// that can be crafted. Reagents that can only be bought add nothing.
+    /// `None` if the item can't be crafted
+    pub fn get(&self, token_address: &str) -> Result<Option<Recipe>, RecipeError> {
+        self.expand(token_address, &mut vec![])
+    }
+
+    /// `visiting` holds the items being expanded further up the recipe tree,
+    /// to report cycles rather than recurse forever
+    fn expand(&self, token_address: &str, visiting: &mut Vec<String>) -> Result<Option<Recipe>, RecipeError> {
+        let ingredients = match self.ingredients(token_address) {
+            Some(ingredients) => ingredients,
+            None => return Ok(None),
+        };
+
+        if let Some(start) = visiting.iter().position(|item| item == token_address) {
+            let cycle = visiting[start..]
+                .iter()
+                .map(String::as_str)
+                .chain([token_address])
+                .map(|item| self.title(item).unwrap_or(item).to_string())
+                .collect();
+
+            return Err(RecipeError::Cycle(cycle));
+        }
+        visiting.push(token_address.to_string());
+
+        let mut recipe = Recipe::default();
+
+        for (ingredient, amount) in ingredients {
+            match ingredient {
+                Ingredient::Ki => {
+                    recipe.reagents.push((String::from("KI"), amount));
+                    recipe.ki_cost += amount;
+                }
+                Ingredient::Gene => {
+                    recipe.reagents.push((String::from("(s)GENE"), amount));
+                    recipe.gene_cost += amount;
+                }
+                Ingredient::Item(reagent) => {
+                    let title = self
+                        .title(&reagent)
+                        .ok_or_else(|| RecipeError::UnknownReagent(reagent.clone()))?
+                        .to_string();
+
+                    if let Some(sub_recipe) = self.expand(&reagent, visiting)? {
+                        recipe.ki_cost += sub_recipe.ki_cost * amount;
+                        recipe.gene_cost += sub_recipe.gene_cost * amount;
+                    }
+
+                    recipe.reagents.push((title, amount));
+                }
+            }
+        }
+
+        visiting.pop();
+
+        Ok(Some(recipe))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecipeError {
+    /// The item needs itself, as the titles of the items along the way, from
+    /// the item back to itself
+    Cycle(Vec<String>),
+    /// A reagent that isn't one of the markets, by token address
+    UnknownReagent(String),
+}
+
+impl fmt::Display for RecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecipeError::Cycle(items) => write!(f, "Recipe cycle: {}", items.join(" → ")),
+            RecipeError::UnknownReagent(token_address) => write!(f, "Unknown reagent {token_address}"),
+        }
+    }
+}
+
+fn to_ingredients(ingredients: &RecipeIngredients) -> Vec<(Ingredient, Decimal)> {
+    ingredients
+        .iter()
+        .map(|(reagent, amount)| {
+            let ingredient = match reagent.as_str() {
+                KI_MINT => Ingredient::Ki,
+                "GENE" => Ingredient::Gene,
+                _ => Ingredient::Item(reagent.clone()),
+            };
+
+            (ingredient, *amount)
+        })
+        .collect()
+}
+
+#[derive(Default)]
+pub struct Recipe {
+    pub ki_cost: Decimal,
+    pub gene_cost: Decimal,
+    pub reagents: Vec<(String, Decimal)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Ingredient {
+    Ki,
+    Gene,
+    /// Another SFT, by token address
+    Item(String),
+}
@@ -0,0 +1,38 @@
+use crate::rpc::parse_base58_pubkey;
+use serde::Deserialize;
+use solana_program::{pubkey, pubkey::Pubkey};
+
+pub const SERUM_V4: Pubkey = pubkey!("srmv4uTCPF81hWDaPyEN2mLZ8XbvzuEM6LsAxR8NpjU");
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MagicEdenItem {
+    // Only used to cross-check the addresses derived from the market state
+    #[serde(default)]
+    pub base_vault_address: Option<String>,
+    #[serde(default)]
+    pub asks_address: Option<String>,
+    #[serde(deserialize_with = "parse_base58_pubkey")]
+    pub market_address: Pubkey,
+    pub token_address: String,
+    pub token_image: String,
+    pub token_title: String,
+    pub collection: String,
+}
+
+impl MagicEdenItem {
+    /// Address of the dex-v4 account holding the owner's orders and pending
+    /// funds in this market
+    pub fn user_account_address(&self, owner: &Pubkey) -> Pubkey {
+        let seeds: [&[u8]; 2] = [&self.market_address.to_bytes(), &owner.to_bytes()];
+
+        Pubkey::find_program_address(&seeds, &SERUM_V4).0
+    }
+}
+
+/// Every known Genopets SFT market
+pub fn genopets_markets() -> Vec<MagicEdenItem> {
+    let genopets_sfts = include_str!("../../collections/genopets_sfts.json");
+
+    serde_json::from_str(genopets_sfts).unwrap()
+}
@@ -0,0 +1,201 @@
+use crate::FetchError;
+use rust_decimal::Decimal;
+use serde::{de, Deserialize};
+use serde_json::{json, Value};
+use solana_program::pubkey::Pubkey;
+use std::{collections::HashMap, str::FromStr};
+
+pub const DEFAULT_RPC_URL: &str = "https://try-rpc.mainnet.solana.blockdaemon.tech/";
+
+/// Body of a `getMultipleAccounts` request. Nodes accept at most 100
+/// addresses per request
+pub fn get_multiple_accounts_request(addresses: &[String]) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "getMultipleAccounts",
+        "params": [
+            addresses,
+            { "encoding": "jsonParsed" }
+        ]
+    })
+}
+
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+/// Body of a `getTokenAccountsByOwner` request for every SPL token account of
+/// the owner
+pub fn get_token_accounts_by_owner_request(owner: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "getTokenAccountsByOwner",
+        "params": [
+            owner,
+            { "programId": TOKEN_PROGRAM_ID },
+            { "encoding": "jsonParsed" }
+        ]
+    })
+}
+
+#[derive(Deserialize, Debug)]
+pub struct JsonRpcResult<T> {
+    // id: u64,
+    // jsonrpc: String,
+    result: Option<JsonRpcResultBody<T>>,
+    error: Option<JsonRpcError>,
+}
+
+impl<T> JsonRpcResult<T> {
+    pub fn into_value(self) -> Result<T, FetchError> {
+        match (self.result, self.error) {
+            (_, Some(error)) => Err(error.into()),
+            (Some(result), None) => Ok(result.value),
+            (None, None) => Err(FetchError::Missing(String::from("RPC result"))),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct JsonRpcResultBody<T> {
+    // context: JsonRpcContext,
+    pub value: T,
+}
+
+// #[derive(Deserialize, Debug)]
+// #[serde(rename_all = "camelCase")]
+// struct JsonRpcContext {
+//     api_version: String,
+//     slot: u64,
+// }
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl From<JsonRpcError> for FetchError {
+    fn from(error: JsonRpcError) -> Self {
+        FetchError::JsonRpc {
+            code: error.code,
+            message: error.message,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UiAccount {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+    pub rent_epoch: u64,
+}
+
+/// Account as sent by the RPC node. Data decoding is deferred so its failures
+/// can be told apart from malformed responses
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodedUiAccount {
+    pub lamports: u64,
+    pub data: Vec<String>,
+    #[serde(deserialize_with = "parse_base58_pubkey")]
+    pub owner: Pubkey,
+    pub executable: bool,
+    #[serde(default)]
+    pub rent_epoch: u64,
+}
+
+impl TryFrom<EncodedUiAccount> for UiAccount {
+    type Error = FetchError;
+
+    fn try_from(account: EncodedUiAccount) -> Result<Self, Self::Error> {
+        Ok(UiAccount {
+            lamports: account.lamports,
+            data: parse_account_data(&account.data)?,
+            owner: account.owner,
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+        })
+    }
+}
+
+/// Decodes the accounts of a `getMultipleAccounts` response
+pub fn decode_accounts(
+    res: JsonRpcResult<Vec<Option<EncodedUiAccount>>>,
+) -> Result<Vec<Option<UiAccount>>, FetchError> {
+    res.into_value()?
+        .into_iter()
+        .map(|account| account.map(UiAccount::try_from).transpose())
+        .collect()
+}
+
+pub fn parse_account_data(data: &[String]) -> Result<Vec<u8>, FetchError> {
+    if data.len() != 2 {
+        return Err(FetchError::Base64(String::from("Invalid array length")));
+    }
+
+    match data[1].as_str() {
+        "base64" => base64::decode(&data[0]).map_err(|e| FetchError::Base64(e.to_string())),
+        _ => Err(FetchError::Base64(format!(
+            "Unsupported encoding: {}",
+            data[1]
+        ))),
+    }
+}
+
+pub fn parse_base58_pubkey<'de, D: de::Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+    let val: String = Deserialize::deserialize(deserializer)?;
+
+    Pubkey::from_str(&val).map_err(|e| de::Error::custom(e.to_string()))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct KeyedTokenAccount {
+    account: ParsedTokenAccount,
+}
+
+#[derive(Deserialize, Debug)]
+struct ParsedTokenAccount {
+    data: ParsedTokenAccountData,
+}
+
+#[derive(Deserialize, Debug)]
+struct ParsedTokenAccountData {
+    parsed: ParsedTokenAccountInfo,
+}
+
+#[derive(Deserialize, Debug)]
+struct ParsedTokenAccountInfo {
+    info: TokenAccountInfo,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct TokenAccountInfo {
+    mint: String,
+    token_amount: TokenAmount,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct TokenAmount {
+    ui_amount_string: Decimal,
+}
+
+/// Balances by mint of a `getTokenAccountsByOwner` response, adding up the
+/// accounts that hold the same mint
+pub fn decode_token_balances(
+    res: JsonRpcResult<Vec<KeyedTokenAccount>>,
+) -> Result<HashMap<String, Decimal>, FetchError> {
+    let mut balances = HashMap::new();
+
+    for keyed_account in res.into_value()? {
+        let info = keyed_account.account.data.parsed.info;
+
+        *balances.entry(info.mint).or_default() += info.token_amount.ui_amount_string;
+    }
+
+    Ok(balances)
+}
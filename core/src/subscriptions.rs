@@ -0,0 +1,194 @@
+use crate::rpc::{EncodedUiAccount, JsonRpcResultBody, UiAccount};
+use crate::{FetchError, Listings, MarketAccounts, Side, SideUpdate};
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::future::Future;
+
+pub const MIN_RECONNECT_DELAY_MS: u32 = 1_000;
+pub const MAX_RECONNECT_DELAY_MS: u32 = 30_000;
+
+/// State of the `accountSubscribe` subscriptions to every bids and asks
+/// account. Knows nothing about the socket itself, it's fed the messages the
+/// server sends.
+pub struct OrderSubscriptions {
+    /// Subscribed slabs, the position is used as the request id
+    slabs: Vec<(String, Side, String)>,
+    /// Subscription ids to their position in `slabs`
+    subscriptions: HashMap<u64, usize>,
+}
+
+impl OrderSubscriptions {
+    pub fn new(market_accounts: &HashMap<String, MarketAccounts>) -> Self {
+        let slabs = market_accounts
+            .iter()
+            .flat_map(|(token_address, accounts)| {
+                [
+                    (token_address.clone(), Side::Asks, accounts.asks.to_string()),
+                    (token_address.clone(), Side::Bids, accounts.bids.to_string()),
+                ]
+            })
+            .collect();
+
+        Self {
+            slabs,
+            subscriptions: HashMap::new(),
+        }
+    }
+
+    /// `accountSubscribe` requests for every slab
+    pub fn requests(&self) -> impl Iterator<Item = String> + '_ {
+        self.slabs.iter().enumerate().map(|(id, (_, _, address))| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "accountSubscribe",
+                "params": [
+                    address,
+                    { "encoding": "base64", "commitment": "confirmed" }
+                ]
+            })
+            .to_string()
+        })
+    }
+
+    /// Whether the server confirmed any of the subscriptions
+    pub fn is_subscribed(&self) -> bool {
+        !self.subscriptions.is_empty()
+    }
+
+    /// Forgets the subscriptions when the connection drops, their ids don't
+    /// survive a reconnection
+    pub fn disconnected(&mut self) {
+        self.subscriptions.clear();
+    }
+
+    /// Handles a message received from the server. Returns the side that
+    /// changed, if the message was a notification of a known subscription
+    pub fn handle_message(&mut self, message: &str) -> Result<Option<SideUpdate>, FetchError> {
+        let message = serde_json::from_str(message)
+            .map_err(|e| FetchError::Json(format!("unexpected subscription message {message}: {e}")))?;
+
+        let params = match message {
+            WsMessage::Response { id, result } => {
+                // Ids are only ours if they point to a slab
+                if id < self.slabs.len() {
+                    self.subscriptions.insert(result, id);
+                }
+
+                return Ok(None);
+            }
+            WsMessage::Notification { params } => params,
+        };
+
+        let (token_address, side, address) = match self
+            .subscriptions
+            .get(&params.subscription)
+            .and_then(|position| self.slabs.get(*position))
+        {
+            Some(slab) => slab,
+            None => return Ok(None),
+        };
+
+        let mut account = UiAccount::try_from(params.result.value)?;
+        let listings = Listings::from_buffer(&mut account.data, *side)
+            .map(Listings::to_vec)
+            .map_err(|e| FetchError::SlabDecode {
+                address: address.clone(),
+                reason: e.to_string(),
+            })?;
+
+        Ok(Some(SideUpdate {
+            token_address: token_address.clone(),
+            side: *side,
+            listings,
+        }))
+    }
+}
+
+/// Keeps the subscriptions running over the connections opened by `connect`,
+/// which yields the socket's sending and receiving halves as text. Whenever
+/// the connection drops, it waits with `sleep` and reconnects, doubling the
+/// delay up to `MAX_RECONNECT_DELAY_MS` while connections keep failing.
+/// Never returns.
+pub async fn keep_subscribed<C, CF, W, R, S, SF>(
+    mut subscriptions: OrderSubscriptions,
+    mut connect: C,
+    mut sleep: S,
+    mut on_update: impl FnMut(SideUpdate),
+    mut log: impl FnMut(String),
+) where
+    C: FnMut() -> CF,
+    CF: Future<Output = Result<(W, R), String>>,
+    W: Sink<String> + Unpin,
+    W::Error: Display,
+    R: Stream<Item = Result<String, String>> + Unpin,
+    S: FnMut(u32) -> SF,
+    SF: Future<Output = ()>,
+{
+    let mut delay = MIN_RECONNECT_DELAY_MS;
+
+    loop {
+        match run(&mut subscriptions, &mut connect, &mut on_update, &mut log).await {
+            // The connection was up and running, so there's no need to keep
+            // increasing the delay
+            Ok(()) => delay = MIN_RECONNECT_DELAY_MS,
+            Err(e) => log(format!("Subscription error: {e}")),
+        }
+
+        log(format!("Subscriptions disconnected, reconnecting in {delay}ms"));
+        sleep(delay).await;
+        delay = (delay * 2).min(MAX_RECONNECT_DELAY_MS);
+    }
+}
+
+async fn run<C, CF, W, R>(
+    subscriptions: &mut OrderSubscriptions,
+    connect: &mut C,
+    on_update: &mut impl FnMut(SideUpdate),
+    log: &mut impl FnMut(String),
+) -> Result<(), String>
+where
+    C: FnMut() -> CF,
+    CF: Future<Output = Result<(W, R), String>>,
+    W: Sink<String> + Unpin,
+    W::Error: Display,
+    R: Stream<Item = Result<String, String>> + Unpin,
+{
+    let (mut write, mut read) = connect().await?;
+
+    subscriptions.disconnected();
+
+    for request in subscriptions.requests().collect::<Vec<_>>() {
+        write.send(request).await.map_err(|e| e.to_string())?;
+    }
+
+    while let Some(message) = read.next().await {
+        match subscriptions.handle_message(&message?) {
+            Ok(Some(update)) => on_update(update),
+            Ok(None) => (),
+            Err(e) => log(e.to_string()),
+        }
+    }
+
+    if !subscriptions.is_subscribed() {
+        return Err(String::from("Connection closed before subscribing"));
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WsMessage {
+    Notification { params: NotificationParams },
+    Response { id: usize, result: u64 },
+}
+
+#[derive(Deserialize)]
+struct NotificationParams {
+    result: JsonRpcResultBody<EncodedUiAccount>,
+    subscription: u64,
+}
@@ -0,0 +1,53 @@
+use crate::{FeeModel, MarketOrders, Side};
+use rust_decimal::Decimal;
+
+/// What buying a given quantity off the asks would take, fees included
+#[derive(Clone, Debug, PartialEq)]
+pub struct SweepQuote {
+    pub requested_quantity: u64,
+    /// Less than requested when the book isn't deep enough
+    pub filled_quantity: u64,
+    pub total_sol: Decimal,
+    pub average_price: Option<Decimal>,
+    /// Price of the last ask that has to be taken
+    pub worst_price: Option<Decimal>,
+}
+
+impl SweepQuote {
+    pub fn has_enough_depth(&self) -> bool {
+        self.filled_quantity >= self.requested_quantity
+    }
+}
+
+impl MarketOrders {
+    /// Takes the cheapest asks until the quantity is filled or the book runs
+    /// out
+    pub fn sweep(&self, quantity: u64, fees: &FeeModel) -> SweepQuote {
+        let mut quote = SweepQuote {
+            requested_quantity: quantity,
+            filled_quantity: 0,
+            total_sol: Decimal::ZERO,
+            average_price: None,
+            worst_price: None,
+        };
+
+        for listing in self.sorted(Side::Asks) {
+            if quote.filled_quantity >= quantity {
+                break;
+            }
+
+            let taken = listing.base_quantity.min(quantity - quote.filled_quantity);
+            let price = fees.buyer_pays(listing.price);
+
+            quote.filled_quantity += taken;
+            quote.total_sol += price * Decimal::from(taken);
+            quote.worst_price = Some(price);
+        }
+
+        if quote.filled_quantity > 0 {
+            quote.average_price = Some(quote.total_sol / Decimal::from(quote.filled_quantity));
+        }
+
+        quote
+    }
+}
@@ -0,0 +1,110 @@
+use crate::Trade;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TradeWindow {
+    Day,
+    Week,
+    #[default]
+    Month,
+}
+
+impl TradeWindow {
+    pub const ALL: [TradeWindow; 3] = [TradeWindow::Day, TradeWindow::Week, TradeWindow::Month];
+
+    pub fn duration(&self) -> Duration {
+        match self {
+            TradeWindow::Day => Duration::days(1),
+            TradeWindow::Week => Duration::days(7),
+            TradeWindow::Month => Duration::days(30),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TradeWindow::Day => "24h",
+            TradeWindow::Week => "7d",
+            TradeWindow::Month => "30d",
+        }
+    }
+}
+
+/// Statistics of the trades in a window ending now. Prices are `None` when
+/// there are no trades
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WindowStats {
+    pub count: usize,
+    /// Units traded
+    pub volume: Decimal,
+    /// SOL traded
+    pub sol_volume: Decimal,
+    pub vwap: Option<Decimal>,
+    pub min: Option<Decimal>,
+    pub max: Option<Decimal>,
+    /// Median price of the trades, regardless of their size
+    pub median: Option<Decimal>,
+    /// Change of the VWAP against the window before, in percent. `None` if
+    /// either window has no trades, like the one before 30 days when only
+    /// that much history is loaded
+    pub change_percent: Option<Decimal>,
+}
+
+impl WindowStats {
+    pub fn new(trades: &[Trade], window: TradeWindow, now: DateTime<Utc>) -> Self {
+        let start = now - window.duration();
+        let previous_start = start - window.duration();
+
+        let in_window = |from: DateTime<Utc>, to: DateTime<Utc>| {
+            trades
+                .iter()
+                .filter(move |trade| trade.ts > from && trade.ts <= to)
+        };
+
+        let mut stats = Self::from_trades(in_window(start, now));
+        let previous = Self::from_trades(in_window(previous_start, start));
+
+        stats.change_percent = match (stats.vwap, previous.vwap) {
+            (Some(vwap), Some(previous_vwap)) if !previous_vwap.is_zero() => {
+                Some((vwap - previous_vwap) / previous_vwap * Decimal::ONE_HUNDRED)
+            }
+            _ => None,
+        };
+
+        stats
+    }
+
+    fn from_trades<'a>(trades: impl Iterator<Item = &'a Trade>) -> Self {
+        let mut prices = vec![];
+        let mut volume = Decimal::ZERO;
+        let mut value = Decimal::ZERO;
+
+        for trade in trades {
+            prices.push(trade.price);
+            volume += trade.amount;
+            value += trade.price * trade.amount;
+        }
+
+        prices.sort();
+
+        Self {
+            count: prices.len(),
+            volume,
+            sol_volume: value,
+            vwap: value.checked_div(volume),
+            min: prices.first().copied(),
+            max: prices.last().copied(),
+            median: median(&prices),
+            change_percent: None,
+        }
+    }
+}
+
+/// Median of sorted values
+pub(crate) fn median(values: &[Decimal]) -> Option<Decimal> {
+    match values.len() {
+        0 => None,
+        len if len % 2 == 1 => Some(values[len / 2]),
+        len => Some((values[len / 2 - 1] + values[len / 2]) / Decimal::TWO),
+    }
+}
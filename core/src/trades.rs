@@ -0,0 +1,159 @@
+use crate::{FeeModel, Side};
+use asset_agnostic_orderbook::state::event_queue::{EventQueue, EventRef};
+use asset_agnostic_orderbook::state::AccountTag;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use dex_v4::state::CallBackInfo;
+use rust_decimal::Decimal;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+/// Saved Flipside query returning the last 30 days of trades per market
+pub const FLIPSIDE_TRADES_URL: &str = "https://node-api.flipsidecrypto.com/api/v2/queries/b76d9ca9-cc22-48d8-9917-6760c1ec5a50/data/latest";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct SftTrades {
+    pub base_vault: String,
+    pub trades: Vec<Trade>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Trade {
+    #[serde(deserialize_with = "deserialize_ts")]
+    pub ts: DateTime<Utc>,
+    pub amount: Decimal,
+    pub price: Decimal,
+    /// Only known to have happened at or before `ts`, see `FillTrades`
+    #[serde(default)]
+    pub approximate: bool,
+}
+
+fn deserialize_ts<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+    let ts = String::deserialize(deserializer)?;
+
+    parse_ts(&ts).ok_or_else(|| D::Error::custom(format!("invalid trade time {ts}")))
+}
+
+/// Trade times are `YYYY-MM-DD HH:MM:SS.fff` in UTC, RFC 3339 is accepted too
+pub fn parse_ts(ts: &str) -> Option<DateTime<Utc>> {
+    match NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S%.f") {
+        Ok(ts) => Some(Utc.from_utc_datetime(&ts)),
+        Err(_) => DateTime::parse_from_rfc3339(ts).ok().map(|ts| ts.with_timezone(&Utc)),
+    }
+}
+
+/// Match between a resting order and a taker, as found in the market's event
+/// queue until the cranker consumes it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fill {
+    /// Position of the event among every event ever pushed to the queue,
+    /// unique per market
+    pub seq_num: u64,
+    pub maker_order_id: u128,
+    /// dex-v4 user accounts of both sides
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub taker_side: Side,
+    pub base_size: u64,
+    pub quote_size: u64,
+}
+
+impl Fill {
+    /// The fill as a trade at the maker's price, `None` for empty fills.
+    /// Event queues don't record when the fill happened, so the trade is
+    /// approximate, `ts` being when it was found
+    pub fn to_trade(&self, fees: &FeeModel, ts: DateTime<Utc>) -> Option<Trade> {
+        Some(Trade {
+            ts,
+            amount: fees.base_amount(self.base_size),
+            price: fees.fill_price(self.base_size, self.quote_size)?,
+            approximate: true,
+        })
+    }
+}
+
+/// Fill events of an event queue account, oldest first. Out events (orders
+/// leaving the book) are skipped
+pub fn decode_fills(buf: &mut [u8]) -> Result<Vec<Fill>, ProgramError> {
+    let event_queue = EventQueue::<CallBackInfo>::from_buffer(buf, AccountTag::EventQueue)?;
+    // The sequence number counts every event pushed, the queue holds the last
+    // `count` of them
+    let first_seq_num = event_queue.header.seq_num - event_queue.header.count;
+
+    let fills = event_queue
+        .iter()
+        .enumerate()
+        .filter_map(|(index, event)| match event {
+            EventRef::Fill(fill) => Some(Fill {
+                seq_num: first_seq_num + index as u64,
+                maker_order_id: fill.event.maker_order_id,
+                maker: fill.maker_callback_info.user_account,
+                taker: fill.taker_callback_info.user_account,
+                taker_side: match fill.event.taker_side {
+                    0 => Side::Bids,
+                    _ => Side::Asks,
+                },
+                base_size: fill.event.base_size,
+                quote_size: fill.event.quote_size,
+            }),
+            EventRef::Out(_) => None,
+        })
+        .collect();
+
+    Ok(fills)
+}
+
+/// Trades from the fills of one market's event queue, kept across loads of
+/// the queue.
+///
+/// Event queues don't record when a fill happened, so every fill is stamped
+/// with the time of the load that found it. Fills showing up in a later load
+/// happened since the previous one, but the ones already queued on the first
+/// load could be any age.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FillTrades {
+    /// Highest sequence number seen, `None` until a fill is
+    last_seq_num: Option<u64>,
+    /// `None` before the first load
+    last_load: Option<DateTime<Utc>>,
+    /// Newest first, as (sequence number, time of the load before, trade).
+    /// Fills of the first load have no load before
+    trades: Vec<(u64, Option<DateTime<Utc>>, Trade)>,
+}
+
+impl FillTrades {
+    /// Adds the fills of a load of the queue, oldest first
+    pub fn load(&mut self, fills: &[Fill], fees: &FeeModel, now: DateTime<Utc>) {
+        let since = self.last_load;
+
+        for fill in fills.iter().filter(|fill| Some(fill.seq_num) > self.last_seq_num) {
+            if let Some(trade) = fill.to_trade(fees, now) {
+                self.trades.insert(0, (fill.seq_num, since, trade));
+            }
+        }
+
+        let max_seq_num = fills.iter().map(|fill| fill.seq_num).max();
+        self.last_seq_num = self.last_seq_num.max(max_seq_num);
+        self.last_load = Some(now);
+    }
+
+    /// Drops the trades the history has caught up with: a trade of the same
+    /// amount and price between the load that found the fill and the one
+    /// before, or any time before for the fills of the first load
+    pub fn dedup(&mut self, history: &[Trade]) {
+        self.trades.retain(|(_, since, trade)| {
+            !history.iter().any(|known| {
+                known.amount == trade.amount
+                    && known.price == trade.price
+                    && since.map_or(true, |since| known.ts > since)
+                    && known.ts <= trade.ts
+            })
+        });
+    }
+
+    /// Newest first
+    pub fn trades(&self) -> impl Iterator<Item = &Trade> {
+        self.trades.iter().map(|(_, _, trade)| trade)
+    }
+}
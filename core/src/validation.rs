@@ -0,0 +1,200 @@
+//! Checks over the raw recipes data file. Only depends on `serde_json` so the
+//! build script can run it too.
+
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+/// Recipe ingredients that aren't SFTs: the KI mint and GENE. Defined here
+/// rather than next to the mints so the build script can use it
+pub const CURRENCIES: [&str; 2] = ["kiGenopAScF8VF31Zbtx2Hg8qA5ArGqvnVtXb83sotc", "GENE"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecipeIssue {
+    /// The file doesn't follow the schema at all
+    Malformed(String),
+    /// Ingredient that is neither a currency nor a known SFT
+    UnknownReagent { item: String, reagent: String },
+    /// Amounts have to be non-negative numbers, not strings or anything else
+    AmountType {
+        item: String,
+        reagent: String,
+        amount: Value,
+    },
+    /// Items that (indirectly) need themselves to be made, in order
+    Cycle(Vec<String>),
+    /// Costed item missing from the market registry, so it's never shown
+    UnreachableItem(String),
+    /// Item with both a base cost and a recipe
+    DuplicateCost(String),
+}
+
+impl fmt::Display for RecipeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecipeIssue::Malformed(reason) => write!(f, "malformed recipes file: {reason}"),
+            RecipeIssue::UnknownReagent { item, reagent } => {
+                write!(f, "{item}: unknown reagent {reagent}")
+            }
+            RecipeIssue::AmountType {
+                item,
+                reagent,
+                amount,
+            } => write!(
+                f,
+                "{item}: amount of {reagent} should be a non-negative number, found {amount}"
+            ),
+            RecipeIssue::Cycle(path) => write!(f, "cycle: {}", path.join(" -> ")),
+            RecipeIssue::UnreachableItem(item) => {
+                write!(f, "{item}: not in the market registry")
+            }
+            RecipeIssue::DuplicateCost(item) => {
+                write!(f, "{item}: has both a base cost and a recipe")
+            }
+        }
+    }
+}
+
+impl RecipeIssue {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RecipeIssue::Malformed(_) => "malformed",
+            RecipeIssue::UnknownReagent { .. } => "unknown reagent",
+            RecipeIssue::AmountType { .. } => "amount type",
+            RecipeIssue::Cycle(_) => "cycle",
+            RecipeIssue::UnreachableItem(_) => "unreachable item",
+            RecipeIssue::DuplicateCost(_) => "duplicate cost",
+        }
+    }
+}
+
+/// Validates the recipes file against the token addresses of the market
+/// registry. `currencies` are the ingredients that aren't SFTs (KI and GENE).
+/// Issues are sorted so the output is stable.
+pub fn validate_recipes(data: &Value, items: &HashSet<String>, currencies: &[&str]) -> Vec<RecipeIssue> {
+    let section = |name: &str| match data.get(name) {
+        Some(Value::Object(entries)) => Ok(entries),
+        _ => Err(RecipeIssue::Malformed(format!("{name} should be an object"))),
+    };
+
+    let (base_costs, recipes) = match (section("baseCosts"), section("recipes")) {
+        (Ok(base_costs), Ok(recipes)) => (base_costs, recipes),
+        (Err(e), _) | (_, Err(e)) => return vec![e],
+    };
+
+    let mut issues = vec![];
+    // Item to the SFTs it's made of
+    let mut graph = BTreeMap::new();
+
+    for (item, ingredients) in base_costs.iter().chain(recipes) {
+        if !items.contains(item) {
+            issues.push(RecipeIssue::UnreachableItem(item.clone()));
+        }
+
+        let ingredients = match ingredients {
+            Value::Object(ingredients) => ingredients,
+            _ => {
+                issues.push(RecipeIssue::Malformed(format!("{item} should map ingredients to amounts")));
+                continue;
+            }
+        };
+
+        let reagents = check_ingredients(item, ingredients, items, currencies, &mut issues);
+        graph.entry(item.as_str()).or_insert_with(Vec::new).extend(reagents);
+    }
+
+    issues.extend(
+        base_costs
+            .keys()
+            .filter(|item| recipes.contains_key(*item))
+            .map(|item| RecipeIssue::DuplicateCost(item.clone())),
+    );
+    issues.extend(find_cycles(&graph).into_iter().map(RecipeIssue::Cycle));
+
+    issues.sort_by_key(|issue| issue.to_string());
+    issues
+}
+
+/// Reports the issues of one item's ingredients, returning the SFTs among them
+fn check_ingredients<'a>(
+    item: &str,
+    ingredients: &'a Map<String, Value>,
+    items: &HashSet<String>,
+    currencies: &[&str],
+    issues: &mut Vec<RecipeIssue>,
+) -> Vec<&'a str> {
+    let mut reagents = vec![];
+
+    for (reagent, amount) in ingredients {
+        let valid_amount = amount.as_f64().map_or(false, |amount| amount >= 0.0);
+        if !valid_amount {
+            issues.push(RecipeIssue::AmountType {
+                item: item.to_string(),
+                reagent: reagent.clone(),
+                amount: amount.clone(),
+            });
+        }
+
+        if currencies.contains(&reagent.as_str()) {
+            continue;
+        }
+
+        match items.contains(reagent) {
+            true => reagents.push(reagent.as_str()),
+            false => issues.push(RecipeIssue::UnknownReagent {
+                item: item.to_string(),
+                reagent: reagent.clone(),
+            }),
+        }
+    }
+
+    reagents
+}
+
+/// Depth-first search reporting every back edge as the cycle it closes
+fn find_cycles(graph: &BTreeMap<&str, Vec<&str>>) -> Vec<Vec<String>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        InProgress,
+        Done,
+    }
+
+    fn visit<'a>(
+        item: &'a str,
+        graph: &BTreeMap<&'a str, Vec<&'a str>>,
+        states: &mut HashMap<&'a str, State>,
+        path: &mut Vec<&'a str>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        states.insert(item, State::InProgress);
+        path.push(item);
+
+        for &reagent in graph.get(item).into_iter().flatten() {
+            match states.get(reagent) {
+                Some(State::Done) => {}
+                Some(State::InProgress) => {
+                    let start = path.iter().position(|&step| step == reagent).unwrap();
+                    let mut cycle = path[start..].iter().map(|step| step.to_string()).collect::<Vec<_>>();
+                    cycle.push(reagent.to_string());
+
+                    cycles.push(cycle);
+                }
+                None => visit(reagent, graph, states, path, cycles),
+            }
+        }
+
+        path.pop();
+        states.insert(item, State::Done);
+    }
+
+    let mut states = HashMap::new();
+    let mut cycles = vec![];
+
+    for &item in graph.keys() {
+        if !states.contains_key(item) {
+            visit(item, graph, &mut states, &mut vec![], &mut cycles);
+        }
+    }
+
+    cycles
+}
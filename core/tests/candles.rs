@@ -0,0 +1,67 @@
+mod common;
+
+use chrono::Duration;
+use common::{dec, trade, ts};
+use magic_eden_sft_core::{candles, Candle, CandleInterval};
+use rust_decimal::Decimal;
+
+#[test]
+fn aggregates_trades_per_bucket() {
+    // Newest first, like the trade history
+    let trades = [
+        trade("2022-11-01 05:10:00.000", 1, "0.5"),
+        trade("2022-11-01 01:50:00.000", 3, "0.2"),
+        trade("2022-11-01 01:30:00.000", 1, "0.4"),
+        trade("2022-11-01 01:00:00.000", 1, "0.3"),
+    ];
+
+    let hourly = candles(&trades, CandleInterval::Hour);
+
+    assert_eq!(
+        hourly,
+        vec![
+            Candle {
+                start: ts("2022-11-01 01:00:00"),
+                open: dec("0.3"),
+                high: dec("0.4"),
+                low: dec("0.2"),
+                close: dec("0.2"),
+                volume: Decimal::from(5),
+                // (0.3 + 0.4 + 3 * 0.2) / 5
+                vwap: dec("0.26"),
+            },
+            Candle {
+                start: ts("2022-11-01 05:00:00"),
+                open: dec("0.5"),
+                high: dec("0.5"),
+                low: dec("0.5"),
+                close: dec("0.5"),
+                volume: Decimal::ONE,
+                vwap: dec("0.5"),
+            },
+        ]
+    );
+
+    let four_hourly = candles(&trades, CandleInterval::FourHours);
+    assert_eq!(
+        four_hourly.iter().map(|candle| candle.start).collect::<Vec<_>>(),
+        vec![ts("2022-11-01 00:00:00"), ts("2022-11-01 04:00:00")]
+    );
+
+    let daily = candles(&trades, CandleInterval::Day);
+    assert_eq!(daily.len(), 1);
+    assert_eq!((daily[0].open, daily[0].close), (dec("0.3"), dec("0.5")));
+}
+
+#[test]
+fn weeks_start_on_monday() {
+    let monday = ts("2022-10-31 00:00:00");
+
+    // A Tuesday
+    assert_eq!(CandleInterval::Week.bucket_start(ts("2022-11-01 12:00:00")), monday);
+    assert_eq!(CandleInterval::Week.bucket_start(monday), monday);
+    assert_eq!(
+        CandleInterval::Week.bucket_start(monday - Duration::seconds(1)),
+        monday - Duration::days(7)
+    );
+}
@@ -0,0 +1,74 @@
+//! Helpers shared by the integration tests, each test crate only uses some of
+//! them
+#![allow(dead_code)]
+
+use chrono::{DateTime, Utc};
+use magic_eden_sft_core::{parse_ts, FeeModel, Listing, MagicEdenItem, MarketOrders, Trade};
+use rust_decimal::Decimal;
+use solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// Fixed point price of 1 SOL with the default multipliers
+pub const ONE_SOL: u64 = 1_000 << 32;
+
+pub fn dec(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+pub fn ts(ts: &str) -> DateTime<Utc> {
+    parse_ts(ts).unwrap()
+}
+
+pub fn trade(ts: &str, amount: i64, price: &str) -> Trade {
+    Trade {
+        ts: self::ts(ts),
+        amount: Decimal::from(amount),
+        price: dec(price),
+        approximate: false,
+    }
+}
+
+/// No fees, so ask prices come out as listed
+pub fn no_fees() -> FeeModel {
+    FeeModel {
+        royalties_bps: 0,
+        taker_fee_bps: 0,
+        ..FeeModel::default()
+    }
+}
+
+/// Orders from (SOL price, quantity), each with its own owner. Keys are made
+/// of the price and the position, like the orderbook's
+pub fn listings(orders: &[(u64, u64)]) -> Vec<Listing> {
+    orders
+        .iter()
+        .enumerate()
+        .map(|(index, &(sol, base_quantity))| Listing {
+            key: ((sol as u128 * ONE_SOL as u128) << 64) | index as u128,
+            owner: Pubkey::new_unique(),
+            price: sol * ONE_SOL,
+            base_quantity,
+        })
+        .collect()
+}
+
+/// Book with only the given asks, as (SOL price, quantity)
+pub fn asks(asks: &[(u64, u64)]) -> MarketOrders {
+    MarketOrders {
+        bids: vec![],
+        asks: listings(asks),
+    }
+}
+
+/// Market whose title is its token address in upper case
+pub fn item(token_address: &str, collection: &str) -> MagicEdenItem {
+    MagicEdenItem {
+        base_vault_address: None,
+        asks_address: None,
+        market_address: Pubkey::new_unique(),
+        token_address: token_address.to_string(),
+        token_image: String::new(),
+        token_title: token_address.to_uppercase(),
+        collection: collection.to_string(),
+    }
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::{dec, item};
+use magic_eden_sft_core::prices::KI_MINT;
+use magic_eden_sft_core::{
+    craft_opportunities, Acquisition, Costing, FloorPrice, MagicEdenItem, MarketPrices, RecipeData, Recipes,
+};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+fn reagent(token_address: &str) -> MagicEdenItem {
+    item(token_address, "genopets_reagents")
+}
+
+fn ingredients(ingredients: &[(&str, i64)]) -> HashMap<String, Decimal> {
+    ingredients
+        .iter()
+        .map(|(ingredient, amount)| (ingredient.to_string(), Decimal::from(*amount)))
+        .collect()
+}
+
+/// - `a` takes 100 KI and 2 `b`, which is cheaper to craft than to buy
+/// - `c` takes a `d`, which is cheaper to buy
+/// - `e` and `f` each need the other
+fn recipes() -> Recipes {
+    let data = RecipeData {
+        base_costs: HashMap::from([(String::from("b"), ingredients(&[(KI_MINT, 10)]))]),
+        recipes: HashMap::from([
+            (String::from("a"), ingredients(&[(KI_MINT, 100), ("b", 2)])),
+            (String::from("c"), ingredients(&[("d", 1)])),
+            (String::from("d"), ingredients(&[(KI_MINT, 1_000)])),
+            (String::from("e"), ingredients(&[("f", 1)])),
+            (String::from("f"), ingredients(&[("e", 1), (KI_MINT, 1)])),
+        ]),
+    };
+
+    Recipes::from_data(data, ["a", "b", "c", "d", "e", "f"].into_iter().map(reagent).collect())
+}
+
+/// KI at 0.01 SOL, with floors as (token, buy, sell)
+fn market_prices(floors: &[(&str, &str, &str)]) -> MarketPrices {
+    MarketPrices {
+        ki: dec("0.01"),
+        gene: Decimal::ZERO,
+        floors: floors
+            .iter()
+            .map(|(token, buy, sell)| (token.to_string(), FloorPrice { buy: dec(buy), sell: dec(sell) }))
+            .collect(),
+    }
+}
+
+#[test]
+fn picks_the_cheaper_of_crafting_and_buying() {
+    let recipes = recipes();
+    let prices = market_prices(&[("b", "0.5", "0.45"), ("d", "2", "1.9")]);
+    let mut costing = Costing::new(&recipes, &prices);
+
+    assert_eq!(costing.cheapest("b"), Some((dec("0.1"), Acquisition::Craft)));
+    assert_eq!(costing.cheapest("d"), Some((Decimal::TWO, Acquisition::Buy)));
+    // 100 KI plus two crafted `b`
+    assert_eq!(costing.craft_cost("a"), Some(dec("1.2")));
+    assert_eq!(costing.craft_cost("c"), Some(Decimal::TWO));
+    // Neither craftable nor listed
+    assert_eq!(costing.cheapest("nothing"), None);
+}
+
+#[test]
+fn trees_count_only_the_ki_of_crafted_items() {
+    let recipes = recipes();
+
+    let prices = market_prices(&[("b", "0.5", "0.45")]);
+    let root = Costing::new(&recipes, &prices).tree("a").unwrap();
+    // 100 KI plus 10 for each of the two crafted `b`
+    assert_eq!(root.ki, Decimal::from(120));
+
+    let prices = market_prices(&[("b", "0.05", "0.04")]);
+    let root = Costing::new(&recipes, &prices).tree("a").unwrap();
+    let b = root.children.iter().find(|child| child.title == "B").unwrap();
+    assert_eq!(b.decision, Some(Acquisition::Buy));
+    assert_eq!(b.ki, Decimal::ZERO);
+    assert_eq!(root.ki, Decimal::from(100));
+}
+
+#[test]
+fn cuts_cycles() {
+    let recipes = recipes();
+
+    // Buying `e` breaks the cycle
+    let prices = market_prices(&[("e", "5", "4.5")]);
+    let mut costing = Costing::new(&recipes, &prices);
+    assert_eq!(costing.cheapest("e"), Some((dec("5"), Acquisition::Buy)));
+    assert_eq!(costing.craft_cost("f"), Some(dec("5.01")));
+
+    // Nothing does
+    let prices = market_prices(&[]);
+    let mut costing = Costing::new(&recipes, &prices);
+    assert_eq!(costing.cheapest("e"), None);
+    assert_eq!(costing.cheapest("f"), None);
+}
+
+#[test]
+fn lists_items_whose_floor_pays_more_than_crafting() {
+    let recipes = recipes();
+    let markets = ["a", "c"].into_iter().map(reagent).collect::<Vec<_>>();
+    let prices = market_prices(&[("a", "1.6", "1.5"), ("b", "0.5", "0.45"), ("c", "2.1", "1.9"), ("d", "2", "1.9")]);
+
+    let opportunities = craft_opportunities(&recipes, &markets, &prices);
+
+    assert_eq!(opportunities.len(), 1);
+    assert_eq!(opportunities[0].token_address, "a");
+    assert_eq!(opportunities[0].production_cost, dec("1.2"));
+    assert_eq!(opportunities[0].margin_sol, dec("0.3"));
+    assert_eq!(opportunities[0].margin_percent, Decimal::from(25));
+}
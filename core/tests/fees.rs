@@ -0,0 +1,64 @@
+mod common;
+
+use common::dec;
+use magic_eden_sft_core::FeeModel;
+use rust_decimal::{Decimal, MathematicalOps};
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Raw price of a listing, in the orderbook's fixed point
+fn listing_price(sol: &str) -> u64 {
+    let price = dec(sol) * Decimal::from(LAMPORTS_PER_SOL) * Decimal::TWO.powu(32) / Decimal::from(1_000_000);
+
+    price.round().to_string().parse().unwrap()
+}
+
+/// The price Magic Eden displayed for a listing, as the tracker used to
+/// compute it with a divisor calibrated by hand against the marketplace
+fn displayed_price(price: u64) -> Decimal {
+    let numerator = Decimal::from(price) * Decimal::from(1_000_000);
+    let denominator = Decimal::from(LAMPORTS_PER_SOL) * Decimal::TWO.powu(32) * dec("0.9520");
+
+    numerator / denominator
+}
+
+#[test]
+fn buyer_pays_the_displayed_marketplace_price() {
+    let fees = FeeModel::default();
+
+    for sol in ["0.0095", "0.12", "1.5", "4.2"] {
+        let price = listing_price(sol);
+
+        assert_eq!(fees.raw_price(price).round_dp(6), dec(sol));
+        assert_eq!(fees.buyer_pays(price).round_dp(3), displayed_price(price).round_dp(3), "{sol} SOL");
+    }
+}
+
+#[test]
+fn seller_receives_the_price_minus_every_fee() {
+    let fees = FeeModel::default();
+    let price = listing_price("1");
+
+    assert_eq!(fees.buyer_pays(price).round_dp(6), dec("1.0504"));
+    assert_eq!(fees.seller_receives(price).round_dp(6), dec("0.9496"));
+}
+
+#[test]
+fn marketplace_fee_adds_to_the_market_fees() {
+    let fees = FeeModel {
+        marketplace_fee_bps: 200,
+        ..FeeModel::default()
+    };
+
+    assert_eq!(fees.buyer_pays(listing_price("1")).round_dp(6), dec("1.0704"));
+}
+
+#[test]
+fn stable_markets_charge_the_lower_taker_fee() {
+    let fees = FeeModel {
+        taker_fee_bps: 1,
+        ..FeeModel::default()
+    };
+
+    assert_eq!(fees.buyer_pays(listing_price("1")).round_dp(6), dec("1.0501"));
+}
@@ -0,0 +1,240 @@
+//! Decoding of the orderbook slabs, checked against golden files and against
+//! slabs built with the orderbook program's own insertion code.
+//!
+//! Every `tests/golden/*.json` file holds a base64 encoded asks account and the
+//! listings it must decode to. Accounts captured from chain (`mainnet_*.json`)
+//! are recorded with `sft-tracker record-golden <item>`, their listings checked
+//! against the ones Magic Eden shows before committing them.
+//! The synthetic cases below are regenerated with
+//! `UPDATE_GOLDEN=1 cargo test -p magic-eden-sft-core`, and otherwise have to
+//! exist and match the slab built by the current orderbook version byte for
+//! byte.
+
+use asset_agnostic_orderbook::state::{
+    critbit::{LeafNode, Slab},
+    AccountTag,
+};
+use dex_v4::state::CallBackInfo;
+use magic_eden_sft_core::{Listing, Listings, Side};
+use proptest::prelude::*;
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+use std::{collections::BTreeMap, fs, path::PathBuf, str::FromStr};
+
+/// Orders as (price, sequence number, base quantity, owner seed)
+type Order = (u64, u64, u64, u8);
+
+#[derive(Deserialize, Serialize)]
+struct GoldenFile {
+    data: String,
+    listings: Vec<GoldenListing>,
+}
+
+/// Keys don't fit in a JSON number and owners are easier to check in base58
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GoldenListing {
+    key: String,
+    owner: String,
+    price: u64,
+    base_quantity: u64,
+}
+
+impl From<&Listing> for GoldenListing {
+    fn from(listing: &Listing) -> Self {
+        Self {
+            key: listing.key.to_string(),
+            owner: listing.owner.to_string(),
+            price: listing.price,
+            base_quantity: listing.base_quantity,
+        }
+    }
+}
+
+impl From<&GoldenListing> for Listing {
+    fn from(listing: &GoldenListing) -> Self {
+        Self {
+            key: listing.key.parse().unwrap(),
+            owner: Pubkey::from_str(&listing.owner).unwrap(),
+            price: listing.price,
+            base_quantity: listing.base_quantity,
+        }
+    }
+}
+
+fn key(price: u64, seq: u64) -> u128 {
+    ((price as u128) << 64) | seq as u128
+}
+
+fn owner(seed: u8) -> Pubkey {
+    Pubkey::new_from_array([seed; 32])
+}
+
+/// Builds an asks slab able to hold `capacity` orders, inserting them in the
+/// given order
+fn build_asks(capacity: usize, orders: &[Order]) -> Vec<u8> {
+    let mut buf = vec![0; Slab::<CallBackInfo>::compute_allocation_size(capacity)];
+    buf[0] = AccountTag::Asks as u8;
+
+    let mut slab = Slab::<CallBackInfo>::from_buffer(&mut buf, AccountTag::Asks).unwrap();
+
+    for &(price, seq, base_quantity, seed) in orders {
+        let (handle, replaced) = slab
+            .insert_leaf(&LeafNode::new(key(price, seq), base_quantity))
+            .unwrap();
+        assert!(replaced.is_none(), "duplicate key {}", key(price, seq));
+
+        slab.callback_infos[handle as usize].user_account = owner(seed);
+    }
+
+    buf
+}
+
+fn decode(buf: &mut [u8]) -> Vec<Listing> {
+    Listings::from_buffer(buf, Side::Asks).unwrap().to_vec()
+}
+
+/// Listings the orders should decode to: every one of them, by ascending key
+fn expected(orders: &[Order]) -> Vec<Listing> {
+    let mut listings = orders
+        .iter()
+        .map(|&(price, seq, base_quantity, seed)| Listing {
+            key: key(price, seq),
+            owner: owner(seed),
+            price,
+            base_quantity,
+        })
+        .collect::<Vec<_>>();
+    listings.sort_by_key(|listing| listing.key);
+
+    listings
+}
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+/// The synthetic golden cases, as (name, capacity, orders)
+fn golden_cases() -> Vec<(&'static str, usize, Vec<Order>)> {
+    // Powers of two only share their top bits, so every insertion adds a
+    // level to the tree
+    let deep = (0..40).map(|bit| (1 << bit, bit, 1 + bit, bit as u8)).collect();
+    // Same prices in a scrambled order, different sequence numbers
+    let full = (0..64u64)
+        .map(|i| (100 + (i * 37) % 16, i, 1 + (i * 7) % 5, (i % 4) as u8))
+        .collect();
+
+    vec![
+        ("asks_empty", 8, vec![]),
+        ("asks_single_leaf", 8, vec![(42_000, 0, 3, 1)]),
+        ("asks_deep_tree", 40, deep),
+        ("asks_max_capacity", 64, full),
+    ]
+}
+
+#[test]
+fn golden_files() {
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+
+    for (name, capacity, orders) in golden_cases() {
+        let path = golden_dir().join(format!("{name}.json"));
+        let mut buf = build_asks(capacity, &orders);
+
+        assert_eq!(decode(&mut buf), expected(&orders), "{name}: decoded listings");
+
+        if update {
+            let golden = GoldenFile {
+                data: base64::encode(&buf),
+                listings: expected(&orders).iter().map(GoldenListing::from).collect(),
+            };
+
+            fs::create_dir_all(golden_dir()).unwrap();
+            fs::write(&path, serde_json::to_string_pretty(&golden).unwrap()).unwrap();
+            eprintln!("Generated {}, review and commit it", path.display());
+        }
+
+        let golden = fs::read_to_string(&path).unwrap_or_else(|e| {
+            panic!("{}: {e}, generate it with UPDATE_GOLDEN=1", path.display())
+        });
+        let golden: GoldenFile = serde_json::from_str(&golden).unwrap();
+        assert_eq!(base64::encode(&buf), golden.data, "{name}: slab layout changed");
+    }
+
+    let mut checked = 0;
+    let mut captured = 0;
+    for entry in fs::read_dir(golden_dir()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().map_or(true, |extension| extension != "json") {
+            continue;
+        }
+
+        let golden: GoldenFile = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let mut buf = base64::decode(&golden.data).unwrap();
+
+        let expected = golden.listings.iter().map(Listing::from).collect::<Vec<_>>();
+        assert_eq!(decode(&mut buf), expected, "{}", path.display());
+
+        checked += 1;
+        if path.file_name().map_or(false, |name| name.to_string_lossy().starts_with("mainnet_")) {
+            captured += 1;
+        }
+    }
+
+    assert!(checked >= golden_cases().len());
+    assert!(captured > 0, "no asks account captured from chain in {}", golden_dir().display());
+}
+
+#[test]
+fn max_capacity_slab_is_full() {
+    let (_, capacity, orders) = golden_cases().pop().unwrap();
+    let mut buf = build_asks(capacity, &orders);
+
+    let mut slab = Slab::<CallBackInfo>::from_buffer(&mut buf, AccountTag::Asks).unwrap();
+    assert!(slab.insert_leaf(&LeafNode::new(key(1, 1_000), 1)).is_err());
+}
+
+#[test]
+fn rejects_the_wrong_side() {
+    let mut buf = build_asks(8, &[(42_000, 0, 3, 1)]);
+
+    assert!(Listings::from_buffer(&mut buf, Side::Bids).is_err());
+}
+
+proptest! {
+    #[test]
+    fn every_leaf_once_in_price_order(
+        orders in prop::collection::btree_map((1u64..1_000, 0u64..1_000), (1u64..100, any::<u8>()), 0..200),
+        removals in prop::collection::vec(any::<prop::sample::Index>(), 0..50),
+    ) {
+        let orders = orders
+            .into_iter()
+            .map(|((price, seq), (base_quantity, seed))| (price, seq, base_quantity, seed))
+            .collect::<Vec<_>>();
+        let mut buf = build_asks(200, &orders);
+
+        // Removals put nodes back on the free lists, so the handles stop
+        // following insertion order
+        let mut remaining = orders
+            .iter()
+            .map(|order| (key(order.0, order.1), *order))
+            .collect::<BTreeMap<_, _>>();
+
+        if !orders.is_empty() {
+            let mut slab = Slab::<CallBackInfo>::from_buffer(&mut buf, AccountTag::Asks).unwrap();
+
+            for index in removals {
+                let &(price, seq, ..) = index.get(&orders);
+
+                if remaining.remove(&key(price, seq)).is_some() {
+                    prop_assert!(slab.remove_by_key(key(price, seq)).is_some());
+                }
+            }
+        }
+
+        let listings = decode(&mut buf);
+        let remaining = remaining.into_values().collect::<Vec<_>>();
+
+        prop_assert_eq!(&listings, &expected(&remaining));
+        prop_assert!(listings.windows(2).all(|pair| pair[0].price <= pair[1].price));
+    }
+}
@@ -0,0 +1,59 @@
+use magic_eden_sft_core::{MarketMetrics, MarketSort, SortColumn};
+use rust_decimal::Decimal;
+
+fn metrics(name: &str, floor: Option<i64>, listed: u64, production_cost: Option<i64>) -> MarketMetrics {
+    MarketMetrics {
+        name: name.to_string(),
+        floor: floor.map(Decimal::from),
+        floor_proceeds: floor.map(Decimal::from),
+        listed,
+        production_cost: production_cost.map(Decimal::from),
+        ..MarketMetrics::default()
+    }
+}
+
+fn rows() -> Vec<(&'static str, MarketMetrics)> {
+    vec![
+        ("b", metrics("Beta", Some(3), 10, None)),
+        ("a", metrics("alpha", None, 0, Some(1))),
+        ("c", metrics("Gamma", Some(2), 5, Some(1))),
+    ]
+}
+
+fn sorted(column: SortColumn, descending: bool) -> Vec<&'static str> {
+    let mut rows = rows();
+    MarketSort { column, descending }.sort(&mut rows);
+
+    rows.into_iter().map(|(key, _)| key).collect()
+}
+
+#[test]
+fn sorts_in_both_directions() {
+    assert_eq!(sorted(SortColumn::Name, false), vec!["a", "b", "c"]);
+    assert_eq!(sorted(SortColumn::Name, true), vec!["c", "b", "a"]);
+    assert_eq!(sorted(SortColumn::Listed, false), vec!["a", "c", "b"]);
+    assert_eq!(sorted(SortColumn::Listed, true), vec!["b", "c", "a"]);
+}
+
+#[test]
+fn missing_figures_sort_last() {
+    assert_eq!(sorted(SortColumn::Floor, false), vec!["c", "b", "a"]);
+    assert_eq!(sorted(SortColumn::Floor, true), vec!["b", "c", "a"]);
+    // Only c has both a floor and a production cost
+    assert_eq!(sorted(SortColumn::CraftMargin, true), vec!["c", "b", "a"]);
+}
+
+#[test]
+fn craft_margin_is_floor_proceeds_over_production_cost() {
+    let gamma = metrics("Gamma", Some(2), 5, Some(1));
+
+    assert_eq!(gamma.craft_margin(), Some(Decimal::ONE));
+    assert_eq!(metrics("Beta", Some(3), 10, None).craft_margin(), None);
+}
+
+#[test]
+fn columns_round_trip_through_their_keys() {
+    for column in SortColumn::ALL {
+        assert_eq!(SortColumn::from_key(column.key()), Some(column));
+    }
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use chrono::{DateTime, Utc};
+use common::{asks, dec, item, no_fees, trade, ts};
+use magic_eden_sft_core::collection_overviews;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+fn now() -> DateTime<Utc> {
+    ts("2022-11-08 12:00:00")
+}
+
+#[test]
+fn aggregates_items_by_collection() {
+    let markets = vec![item("a", "reagents"), item("b", "reagents"), item("c", "augments")];
+    let orders = HashMap::from([
+        (String::from("a"), asks(&[(1, 2), (3, 1)])),
+        (String::from("b"), asks(&[(2, 5)])),
+    ]);
+    let trades = HashMap::from([
+        (
+            String::from("a"),
+            vec![
+                trade("2022-11-08 10:00:00", 2, "1.5"),
+                trade("2022-11-07 10:00:00", 1, "1"),
+            ],
+        ),
+        (
+            String::from("b"),
+            vec![
+                trade("2022-11-08 09:00:00", 1, "0.9"),
+                trade("2022-11-04 09:00:00", 1, "1"),
+                trade("2022-11-07 09:00:00", 1, "1"),
+            ],
+        ),
+    ]);
+
+    let overviews = collection_overviews(&markets, &orders, &trades, |_| no_fees(), now());
+
+    assert_eq!(
+        overviews.iter().map(|overview| overview.collection.as_str()).collect::<Vec<_>>(),
+        vec!["augments", "reagents"]
+    );
+    assert_eq!(overviews[0].items, 0);
+    assert_eq!(overviews[0].floor, None);
+
+    let reagents = &overviews[1];
+    assert_eq!(reagents.items, 2);
+    assert_eq!(reagents.listed_units, 8);
+    assert_eq!(reagents.floor, Some(Decimal::ONE));
+    assert_eq!(reagents.median_ask, Some(Decimal::TWO));
+    // 2 * 1.5 + 0.9
+    assert_eq!(reagents.volume_24h, dec("3.9"));
+    // Plus the 1 + 1 + 1 of the days before
+    assert_eq!(reagents.volume_7d, dec("6.9"));
+
+    let movers = reagents
+        .movers
+        .iter()
+        .map(|mover| (mover.token_address.as_str(), mover.change_percent))
+        .collect::<Vec<_>>();
+    assert_eq!(movers, vec![("a", Decimal::from(50)), ("b", Decimal::from(-10))]);
+}
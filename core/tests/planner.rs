@@ -0,0 +1,84 @@
+//! Crafting plans over the full `genopets_recipes.json`
+
+mod common;
+
+use common::{asks, dec, no_fees};
+use magic_eden_sft_core::prices::KI_MINT;
+use magic_eden_sft_core::{genopets_markets, CraftPlan, MarketOrders, PlanLine, Recipes};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+const TAIL_REAGENT: &str = "AGEnJ6guwZQDKub7qufdShQc1kn4QdSDJ4cXYvpTDUUn";
+const REFINED_WATER_CRYSTAL: &str = "WATErpZ2ZBjgAxyttoEjckuTuCe9pEckSabCeENLTYq";
+
+fn line(needed: i64, held: i64, crafted: i64, to_buy: i64) -> PlanLine {
+    PlanLine {
+        needed: needed.into(),
+        held: held.into(),
+        crafted: crafted.into(),
+        to_buy: to_buy.into(),
+    }
+}
+
+#[test]
+fn expands_targets_into_base_ingredients() {
+    let recipes = Recipes::new(genopets_markets());
+    let targets = [(TAIL_REAGENT.to_string(), Decimal::from(3))];
+
+    let plan = CraftPlan::new(&recipes, &targets, &HashMap::new());
+
+    // 100 KI and two water crystals each
+    assert_eq!(plan.ki, line(300, 0, 0, 300));
+    assert_eq!(plan.items[TAIL_REAGENT], line(3, 0, 3, 0));
+    assert_eq!(plan.items[REFINED_WATER_CRYSTAL], line(6, 0, 0, 6));
+}
+
+#[test]
+fn subtracts_holdings_below_the_targets() {
+    let recipes = Recipes::new(genopets_markets());
+    let targets = [(TAIL_REAGENT.to_string(), Decimal::from(3))];
+    let holdings = HashMap::from([
+        (KI_MINT.to_string(), Decimal::from(50)),
+        (REFINED_WATER_CRYSTAL.to_string(), Decimal::from(1)),
+        // The target itself is what we want more of
+        (TAIL_REAGENT.to_string(), Decimal::from(2)),
+    ]);
+
+    let plan = CraftPlan::new(&recipes, &targets, &holdings);
+
+    assert_eq!(plan.ki, line(300, 50, 0, 250));
+    assert_eq!(plan.items[TAIL_REAGENT], line(3, 0, 3, 0));
+    assert_eq!(plan.items[REFINED_WATER_CRYSTAL], line(6, 1, 0, 5));
+}
+
+/// Water crystal asks as (SOL price, quantity)
+fn crystal_asks(orders: &[(u64, u64)]) -> HashMap<String, MarketOrders> {
+    HashMap::from([(REFINED_WATER_CRYSTAL.to_string(), asks(orders))])
+}
+
+#[test]
+fn prices_the_shopping_list_off_the_asks() {
+    let recipes = Recipes::new(genopets_markets());
+    let targets = [(TAIL_REAGENT.to_string(), Decimal::from(3))];
+    let plan = CraftPlan::new(&recipes, &targets, &HashMap::new());
+    let ki_price = dec("0.01");
+
+    let shopping = plan.shopping_cost(&crystal_asks(&[(2, 5), (1, 4)]), |_| no_fees(), (ki_price, Decimal::ZERO));
+
+    assert_eq!(shopping.ki_sol, Decimal::from(3));
+    // 4 crystals at 1 SOL and 2 at 2 SOL
+    let crystals = shopping.items[REFINED_WATER_CRYSTAL].as_ref().unwrap();
+    assert_eq!(crystals.total_sol, Decimal::from(8));
+    assert_eq!(shopping.total_sol, Decimal::from(11));
+    assert!(shopping.complete);
+
+    let shopping = plan.shopping_cost(&crystal_asks(&[(1, 4)]), |_| no_fees(), (ki_price, Decimal::ZERO));
+
+    assert_eq!(shopping.total_sol, Decimal::from(7));
+    assert!(!shopping.complete);
+
+    let shopping = plan.shopping_cost(&HashMap::new(), |_| no_fees(), (ki_price, Decimal::ZERO));
+
+    assert_eq!(shopping.items[REFINED_WATER_CRYSTAL], None);
+    assert!(!shopping.complete);
+}
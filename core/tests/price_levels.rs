@@ -0,0 +1,59 @@
+mod common;
+
+use common::{dec, listings, no_fees, ONE_SOL};
+use magic_eden_sft_core::{FeeModel, MarketOrders, Side};
+use rust_decimal::Decimal;
+
+/// Levels as (SOL price, quantity, orders, cumulative quantity, cumulative SOL)
+fn levels(orders: &MarketOrders, side: Side, fees: &FeeModel) -> Vec<(u64, u64, usize, u64, Decimal)> {
+    orders
+        .price_levels(side, fees)
+        .into_iter()
+        .map(|level| {
+            (
+                level.price / ONE_SOL,
+                level.base_quantity,
+                level.order_count,
+                level.cumulative_quantity,
+                level.cumulative_sol,
+            )
+        })
+        .collect()
+}
+
+fn orders() -> MarketOrders {
+    MarketOrders {
+        bids: listings(&[(2, 1), (1, 2), (2, 3)]),
+        asks: listings(&[(3, 1), (1, 2), (1, 1)]),
+    }
+}
+
+#[test]
+fn merges_orders_at_the_same_price_best_first() {
+    assert_eq!(
+        levels(&orders(), Side::Bids, &no_fees()),
+        vec![(2, 4, 2, 4, Decimal::from(8)), (1, 2, 1, 6, Decimal::from(10))]
+    );
+    assert_eq!(
+        levels(&orders(), Side::Asks, &no_fees()),
+        vec![(1, 3, 2, 3, Decimal::from(3)), (3, 1, 1, 4, Decimal::from(6))]
+    );
+}
+
+#[test]
+fn cumulative_sol_includes_fees() {
+    let fees = FeeModel::default();
+    let asks = levels(&orders(), Side::Asks, &fees);
+    let bids = levels(&orders(), Side::Bids, &fees);
+
+    // 3 * 1.0504, then 3 more SOL at the same rate
+    assert_eq!(asks[0].4.round_dp(6), dec("3.1512"));
+    assert_eq!(asks[1].4.round_dp(6), dec("6.3024"));
+    // 8 * 0.9496
+    assert_eq!(bids[0].4.round_dp(6), dec("7.5968"));
+}
+
+#[test]
+fn empty_side_has_no_levels() {
+    assert!(MarketOrders::default().price_levels(Side::Asks, &no_fees()).is_empty());
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::dec;
+use magic_eden_sft_core::prices::{Currency, ExchangeRates};
+use rust_decimal::Decimal;
+
+fn rates() -> ExchangeRates {
+    ExchangeRates {
+        ki: dec("0.00002"),
+        gene: dec("0.0075"),
+        sol_usd: Some(dec("20.5")),
+    }
+}
+
+#[test]
+fn converts_sol_to_every_currency() {
+    let rates = rates();
+
+    assert_eq!(rates.convert(Decimal::TWO, Currency::Sol), Some(Decimal::TWO));
+    assert_eq!(rates.convert(Decimal::TWO, Currency::Usd), Some(Decimal::from(41)));
+    assert_eq!(rates.convert(Decimal::TWO, Currency::Ki), Some(Decimal::from(100_000)));
+    assert_eq!(rates.convert(dec("0.015"), Currency::Gene), Some(Decimal::TWO));
+}
+
+#[test]
+fn zero_rates_dont_convert() {
+    let rates = ExchangeRates {
+        ki: Decimal::ZERO,
+        gene: Decimal::ZERO,
+        sol_usd: Some(Decimal::ZERO),
+    };
+
+    assert_eq!(rates.convert(Decimal::ONE, Currency::Ki), None);
+    assert_eq!(rates.convert(Decimal::ONE, Currency::Gene), None);
+    // Worthless, but not undefined
+    assert_eq!(rates.convert(Decimal::ONE, Currency::Usd), Some(Decimal::ZERO));
+    assert_eq!(rates.convert(Decimal::ONE, Currency::Sol), Some(Decimal::ONE));
+}
+
+#[test]
+fn missing_usd_price_only_rules_out_usd() {
+    let rates = ExchangeRates {
+        sol_usd: None,
+        ..rates()
+    };
+
+    assert_eq!(rates.convert(Decimal::ONE, Currency::Usd), None);
+    assert_eq!(rates.tokens(), (dec("0.00002"), dec("0.0075")));
+}
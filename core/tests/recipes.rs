@@ -0,0 +1,123 @@
+//! Costing over the full `genopets_recipes.json`
+
+mod common;
+
+use common::item;
+use magic_eden_sft_core::prices::KI_MINT;
+use magic_eden_sft_core::{genopets_markets, CraftPlan, RecipeData, RecipeError, Recipes};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+const NEON_ORANGE_LUME_CRYSTAL: &str = "1MEC6M3eFgDAKVMt87aburfTC4ybAbrZSEE5FxT8ar7";
+const ALKALI_FERRO_EAR: &str = "A1KAXs65EFZzNwknVf58CFfzthLME5dYTH3qzee4B7Uw";
+const EYE_REAGENT: &str = "AGENHU6GWPzyPoa3S94QFS6UNNqBjqBVf8aQwiBno9xQ";
+const TAIL_REAGENT: &str = "AGEnJ6guwZQDKub7qufdShQc1kn4QdSDJ4cXYvpTDUUn";
+const SUPERIOR_CIRRUS_WING: &str = "AUGM6idRifG3SJa14rJubvBm28GbDN6yKkpaN2SzFr46";
+const REFINED_FIRE_CRYSTAL: &str = "FireKR7LgjyzjsLnxaNZwa7dnJncDSidD4cXGhTGz2eU";
+const PAGE_13: &str = "P13ofMbQgnq17iPXHRDSt6ia2isCzw4YMZYgCGUtNoo";
+
+fn recipes() -> Recipes {
+    Recipes::new(genopets_markets())
+}
+
+fn assert_cost(token_address: &str, ki_cost: i64, gene_cost: i64) {
+    let recipe = recipes().get(token_address).unwrap().unwrap();
+
+    assert_eq!(
+        (recipe.ki_cost, recipe.gene_cost),
+        (Decimal::from(ki_cost), Decimal::from(gene_cost)),
+        "{token_address}"
+    );
+}
+
+#[test]
+fn crystals_have_a_base_cost() {
+    assert_cost(REFINED_FIRE_CRYSTAL, 10, 0);
+}
+
+#[test]
+fn crystal_reagents_are_counted_once() {
+    // 100 KI, 1 GENE, a fire and a metal crystal
+    assert_cost(NEON_ORANGE_LUME_CRYSTAL, 120, 1);
+    // 100 KI and two water crystals
+    assert_cost(TAIL_REAGENT, 120, 0);
+    // 100 KI, a metal and a wood crystal
+    assert_cost(EYE_REAGENT, 120, 0);
+}
+
+#[test]
+fn sub_recipes_are_costed_recursively() {
+    // A crafted reagent (120 KI), three metal crystals and 2 GENE
+    assert_cost(SUPERIOR_CIRRUS_WING, 150, 2);
+}
+
+#[test]
+fn bought_reagents_add_nothing() {
+    assert!(recipes().ingredients(PAGE_13).is_none());
+
+    // Five metal crystals, 2 GENE and five missing pages
+    assert_cost(ALKALI_FERRO_EAR, 50, 2);
+}
+
+#[test]
+fn every_recipe_is_costed() {
+    let data = RecipeData::genopets();
+    let recipes = recipes();
+
+    for token_address in data.recipes.keys().chain(data.base_costs.keys()) {
+        assert!(matches!(recipes.get(token_address), Ok(Some(_))), "{token_address}");
+    }
+}
+
+#[test]
+fn base_costs_are_not_recipes() {
+    let data = RecipeData::genopets();
+
+    for token_address in data.base_costs.keys() {
+        assert!(!data.recipes.contains_key(token_address), "{token_address}");
+    }
+}
+
+/// `a` needs `b` and `b` needs `a`, `c` needs `x` which isn't a market
+fn broken_recipes() -> Recipes {
+    let ingredients =
+        |reagent: &str| HashMap::from([(reagent.to_string(), Decimal::ONE), (KI_MINT.to_string(), Decimal::TEN)]);
+    let data = RecipeData {
+        base_costs: HashMap::new(),
+        recipes: HashMap::from([
+            (String::from("a"), ingredients("b")),
+            (String::from("b"), ingredients("a")),
+            (String::from("c"), ingredients("x")),
+        ]),
+    };
+
+    Recipes::from_data(data, ["a", "b", "c"].into_iter().map(|token_address| item(token_address, "genopets_reagents")).collect())
+}
+
+#[test]
+fn reports_cycles() {
+    let recipes = broken_recipes();
+
+    assert_eq!(
+        recipes.get("a").err(),
+        Some(RecipeError::Cycle(vec![String::from("A"), String::from("B"), String::from("A")]))
+    );
+}
+
+#[test]
+fn reports_unknown_reagents() {
+    assert_eq!(broken_recipes().get("c").err(), Some(RecipeError::UnknownReagent(String::from("x"))));
+}
+
+#[test]
+fn plans_buy_what_needs_itself() {
+    let recipes = broken_recipes();
+
+    let plan = CraftPlan::new(&recipes, &[(String::from("a"), Decimal::TWO)], &HashMap::new());
+
+    assert_eq!(plan.cycles.iter().collect::<Vec<_>>(), ["a"]);
+    assert_eq!(plan.items["a"].crafted, Decimal::TWO);
+    assert_eq!(plan.items["a"].to_buy, Decimal::TWO);
+    assert_eq!(plan.items["b"].crafted, Decimal::TWO);
+    assert_eq!(plan.ki.needed, Decimal::from(40));
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::dec;
+use magic_eden_sft_core::prices::KI_MINT;
+use magic_eden_sft_core::rpc::{decode_token_balances, JsonRpcResult, KeyedTokenAccount};
+use magic_eden_sft_core::FetchError;
+use rust_decimal::Decimal;
+
+const CRYSTAL_MINT: &str = "WATErpZ2ZBjgAxyttoEjckuTuCe9pEckSabCeENLTYq";
+
+/// One account of a `getTokenAccountsByOwner` response with `jsonParsed`
+/// encoding
+fn token_account(pubkey: &str, mint: &str, amount: &str, decimals: u32) -> String {
+    let ui_amount = dec(amount) / Decimal::from(10u64.pow(decimals));
+
+    format!(
+        r#"{{"account":{{"data":{{"parsed":{{"info":{{"isNative":false,"mint":"{mint}","owner":"7EtuuNs8jqpEUhiCQxf25mUiLzoMuCBkTnjK4YrZC5Bd","state":"initialized","tokenAmount":{{"amount":"{amount}","decimals":{decimals},"uiAmount":{ui_amount},"uiAmountString":"{ui_amount}"}}}},"type":"account"}},"program":"spl-token","space":165}},"executable":false,"lamports":2039280,"owner":"TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA","rentEpoch":361}},"pubkey":"{pubkey}"}}"#
+    )
+}
+
+fn response(accounts: &[String]) -> JsonRpcResult<Vec<KeyedTokenAccount>> {
+    let json = format!(
+        r#"{{"jsonrpc":"2.0","result":{{"context":{{"apiVersion":"1.13.5","slot":166974442}},"value":[{}]}},"id":1}}"#,
+        accounts.join(",")
+    );
+
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn adds_up_the_accounts_of_each_mint() {
+    let res = response(&[
+        token_account("C2gJg6tKpQs41PRS1nC8aw3ZKNZK3HQQZGVrDFDup5nx", KI_MINT, "150000000", 6),
+        token_account("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin", CRYSTAL_MINT, "3", 0),
+        token_account("4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R", KI_MINT, "2500000", 6),
+    ]);
+
+    let balances = decode_token_balances(res).unwrap();
+
+    assert_eq!(balances.len(), 2);
+    assert_eq!(balances[KI_MINT], dec("152.5"));
+    assert_eq!(balances[CRYSTAL_MINT], Decimal::from(3));
+}
+
+#[test]
+fn owner_without_accounts_holds_nothing() {
+    assert!(decode_token_balances(response(&[])).unwrap().is_empty());
+}
+
+#[test]
+fn reports_rpc_errors() {
+    let res = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid param: could not find account"},"id":1}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        decode_token_balances(res),
+        Err(FetchError::JsonRpc {
+            code: -32602,
+            message: String::from("Invalid param: could not find account"),
+        })
+    );
+}
@@ -0,0 +1,198 @@
+use asset_agnostic_orderbook::state::{
+    critbit::{LeafNode, Slab},
+    AccountTag,
+};
+use dex_v4::state::CallBackInfo;
+use futures::{future, SinkExt, StreamExt};
+use magic_eden_sft_core::{keep_subscribed, FeeModel, FetchError, MarketAccounts, OrderSubscriptions, Side};
+use serde_json::Value;
+use solana_program::pubkey::Pubkey;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::WebSocketStream;
+
+const TOKEN_ADDRESS: &str = "token";
+
+/// Asks slab with a single order of 3 units
+fn asks_data() -> String {
+    let mut buf = vec![0; Slab::<CallBackInfo>::compute_allocation_size(8)];
+    buf[0] = AccountTag::Asks as u8;
+
+    let mut slab = Slab::<CallBackInfo>::from_buffer(&mut buf, AccountTag::Asks).unwrap();
+    let (handle, _) = slab.insert_leaf(&LeafNode::new((42_000 << 64) | 1, 3)).unwrap();
+    slab.callback_infos[handle as usize].user_account = Pubkey::new_from_array([1; 32]);
+
+    base64::encode(&buf)
+}
+
+/// Subscriptions to a single market, so the asks get request id 0 and the bids
+/// request id 1
+fn subscriptions() -> OrderSubscriptions {
+    let accounts = MarketAccounts {
+        market: Pubkey::new_unique(),
+        orderbook: Pubkey::new_unique(),
+        bids: Pubkey::new_unique(),
+        asks: Pubkey::new_unique(),
+        event_queue: Pubkey::new_unique(),
+        base_mint: Pubkey::new_unique(),
+        base_vault: Pubkey::new_unique(),
+        quote_vault: Pubkey::new_unique(),
+        fees: FeeModel::default(),
+    };
+
+    OrderSubscriptions::new(&HashMap::from([(TOKEN_ADDRESS.to_string(), accounts)]))
+}
+
+fn subscribed(id: usize, subscription: u64) -> String {
+    format!(r#"{{"jsonrpc":"2.0","result":{subscription},"id":{id}}}"#)
+}
+
+fn notification(subscription: u64, data: &str) -> String {
+    format!(
+        r#"{{"jsonrpc":"2.0","method":"accountNotification","params":{{"result":{{"context":{{"slot":5199307}},"value":{{"data":["{data}","base64"],"executable":false,"lamports":33594,"owner":"11111111111111111111111111111111","rentEpoch":635}}}},"subscription":{subscription}}}}}"#
+    )
+}
+
+#[test]
+fn notifications_update_the_subscribed_side() {
+    let mut subscriptions = subscriptions();
+    assert_eq!(subscriptions.requests().count(), 2);
+
+    assert_eq!(subscriptions.handle_message(&subscribed(0, 23784)), Ok(None));
+    assert!(subscriptions.is_subscribed());
+
+    let update = subscriptions
+        .handle_message(&notification(23784, &asks_data()))
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(update.token_address, TOKEN_ADDRESS);
+    assert_eq!(update.side, Side::Asks);
+    assert_eq!(update.listings.len(), 1);
+    assert_eq!(update.listings[0].price, 42_000);
+    assert_eq!(update.listings[0].base_quantity, 3);
+}
+
+#[test]
+fn disconnecting_forgets_the_subscriptions() {
+    let mut subscriptions = subscriptions();
+    subscriptions.handle_message(&subscribed(0, 23784)).unwrap();
+
+    subscriptions.disconnected();
+
+    assert!(!subscriptions.is_subscribed());
+    assert_eq!(subscriptions.handle_message(&notification(23784, &asks_data())), Ok(None));
+}
+
+#[test]
+fn ignores_unknown_ids() {
+    let mut subscriptions = subscriptions();
+
+    assert_eq!(subscriptions.handle_message(&subscribed(99, 5)), Ok(None));
+    assert!(!subscriptions.is_subscribed());
+    assert_eq!(subscriptions.handle_message(&notification(5, &asks_data())), Ok(None));
+}
+
+#[test]
+fn reports_bad_messages() {
+    let mut subscriptions = subscriptions();
+    subscriptions.handle_message(&subscribed(1, 7)).unwrap();
+
+    assert!(matches!(subscriptions.handle_message("not json"), Err(FetchError::Json(_))));
+    // The bids subscription is sent an asks slab
+    assert!(matches!(
+        subscriptions.handle_message(&notification(7, &asks_data())),
+        Err(FetchError::SlabDecode { .. })
+    ));
+}
+
+/// Accepts the next connection and answers both subscription requests,
+/// confirming the asks one with the given subscription id
+async fn accept_subscriptions(listener: &TcpListener, subscription: u64) -> WebSocketStream<TcpStream> {
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+    let mut ids = vec![];
+    for _ in 0..2 {
+        let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+        let request: Value = serde_json::from_str(&request).unwrap();
+        assert_eq!(request["method"], "accountSubscribe");
+        ids.push(request["id"].as_u64().unwrap());
+    }
+    assert_eq!(ids, [0, 1]);
+
+    ws.send(Message::Text(subscribed(0, subscription))).await.unwrap();
+    ws.send(Message::Text(notification(subscription, &asks_data()))).await.unwrap();
+
+    ws
+}
+
+#[tokio::test]
+async fn reconnects_and_resubscribes_with_backoff() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let delays = RefCell::new(vec![]);
+    let (updates, mut received) = mpsc::unbounded_channel();
+
+    let connect = || {
+        let url = url.clone();
+
+        async move {
+            let (ws, _) = tokio_tungstenite::connect_async(url).await.map_err(|e| e.to_string())?;
+            let (write, read) = ws.split();
+
+            let write = write.with(|text: String| future::ready(Ok::<_, tungstenite::Error>(Message::Text(text))));
+            let read = read.filter_map(|message| {
+                future::ready(match message {
+                    Ok(Message::Text(text)) => Some(Ok(text)),
+                    Ok(_) => None,
+                    Err(e) => Some(Err(e.to_string())),
+                })
+            });
+
+            Ok((write, read))
+        }
+    };
+
+    let client = keep_subscribed(
+        subscriptions(),
+        connect,
+        |delay| {
+            delays.borrow_mut().push(delay);
+            future::ready(())
+        },
+        |update| updates.send(update).unwrap(),
+        |_| (),
+    );
+
+    let server = async {
+        // Two connections dropped before the handshake
+        for _ in 0..2 {
+            drop(listener.accept().await.unwrap());
+        }
+
+        // Subscribed, then closed by the server
+        let mut ws = accept_subscriptions(&listener, 11).await;
+        assert_eq!(received.recv().await.unwrap().side, Side::Asks);
+        ws.close(None).await.unwrap();
+        while ws.next().await.is_some() {}
+
+        // The new connection has to subscribe again
+        let _ws = accept_subscriptions(&listener, 12).await;
+        received.recv().await.unwrap()
+    };
+
+    let update = tokio::select! {
+        _ = client => unreachable!("the client never stops"),
+        update = server => update,
+    };
+
+    assert_eq!(update.token_address, TOKEN_ADDRESS);
+    assert_eq!(update.listings.len(), 1);
+    // Failures double the delay, a connection that was up resets it
+    assert_eq!(*delays.borrow(), [1_000, 2_000, 1_000]);
+}
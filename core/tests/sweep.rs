@@ -0,0 +1,50 @@
+mod common;
+
+use common::{asks, dec, no_fees};
+use magic_eden_sft_core::{FeeModel, MarketOrders};
+use rust_decimal::Decimal;
+
+/// Asks listed out of price order
+fn book() -> MarketOrders {
+    asks(&[(3, 1), (1, 2), (2, 2)])
+}
+
+#[test]
+fn takes_the_cheapest_asks_first() {
+    let quote = book().sweep(3, &no_fees());
+
+    assert!(quote.has_enough_depth());
+    assert_eq!(quote.filled_quantity, 3);
+    // 2 at 1 SOL and 1 at 2 SOL
+    assert_eq!(quote.total_sol, Decimal::from(4));
+    assert_eq!(quote.average_price.unwrap().round_dp(6), dec("1.333333"));
+    assert_eq!(quote.worst_price, Some(Decimal::TWO));
+}
+
+#[test]
+fn fills_what_it_can_when_the_book_is_too_shallow() {
+    let quote = book().sweep(10, &no_fees());
+
+    assert!(!quote.has_enough_depth());
+    assert_eq!(quote.requested_quantity, 10);
+    assert_eq!(quote.filled_quantity, 5);
+    assert_eq!(quote.total_sol, Decimal::from(9));
+    assert_eq!(quote.worst_price, Some(Decimal::from(3)));
+}
+
+#[test]
+fn prices_include_fees() {
+    let quote = book().sweep(2, &FeeModel::default());
+
+    assert_eq!(quote.total_sol.round_dp(6), dec("2.1008"));
+    assert_eq!(quote.worst_price.unwrap().round_dp(6), dec("1.0504"));
+}
+
+#[test]
+fn empty_book_fills_nothing() {
+    let quote = MarketOrders::default().sweep(1, &no_fees());
+
+    assert_eq!(quote.filled_quantity, 0);
+    assert_eq!(quote.average_price, None);
+    assert_eq!(quote.worst_price, None);
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::{dec, trade, ts};
+use magic_eden_sft_core::{Trade, TradeWindow, WindowStats};
+use rust_decimal::Decimal;
+
+fn trades() -> Vec<Trade> {
+    vec![
+        trade("2022-11-08 10:00:00.000", 1, "0.4"),
+        trade("2022-11-08 06:00:00.000", 2, "0.3"),
+        trade("2022-11-07 20:00:00.000", 1, "0.1"),
+        trade("2022-11-07 09:00:00.000", 2, "0.2"),
+        trade("2022-11-01 09:00:00.000", 4, "0.1"),
+    ]
+}
+
+#[test]
+fn window_stats_compare_against_the_window_before() {
+    let stats = WindowStats::new(&trades(), TradeWindow::Day, ts("2022-11-08 12:00:00"));
+
+    assert_eq!(
+        stats,
+        WindowStats {
+            count: 3,
+            volume: Decimal::from(4),
+            sol_volume: dec("1.1"),
+            // (0.4 + 0.6 + 0.1) / 4
+            vwap: Some(dec("0.275")),
+            min: Some(dec("0.1")),
+            max: Some(dec("0.4")),
+            median: Some(dec("0.3")),
+            // Against the 0.2 of the day before
+            change_percent: Some(dec("37.5")),
+        }
+    );
+}
+
+#[test]
+fn even_counts_average_the_median() {
+    let stats = WindowStats::new(&trades(), TradeWindow::Week, ts("2022-11-08 12:00:00"));
+
+    assert_eq!(stats.count, 4);
+    assert_eq!(stats.median, Some(dec("0.25")));
+    // (1.1 + 0.4) / 6 against the 0.1 of the week before
+    assert_eq!(stats.change_percent, Some(dec("150")));
+}
+
+#[test]
+fn empty_windows_have_no_prices() {
+    let stats = WindowStats::new(&trades(), TradeWindow::Day, ts("2022-12-01 00:00:00"));
+
+    assert_eq!(stats, WindowStats::default());
+}
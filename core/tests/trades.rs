@@ -0,0 +1,196 @@
+mod common;
+
+use asset_agnostic_orderbook::state::event_queue::{EventQueue, EventTag, FillEvent};
+use asset_agnostic_orderbook::state::AccountTag;
+use chrono::{DateTime, Duration, Utc};
+use common::{dec, ts};
+use dex_v4::state::CallBackInfo;
+use magic_eden_sft_core::{decode_fills, parse_ts, FeeModel, Fill, FillTrades, Side, Trade};
+use rust_decimal::Decimal;
+use solana_program::pubkey::Pubkey;
+
+fn now() -> DateTime<Utc> {
+    ts("2022-11-01 12:00:00.000")
+}
+
+fn fill(base_size: u64, quote_size: u64) -> Fill {
+    Fill {
+        seq_num: 0,
+        maker_order_id: 1,
+        maker: Pubkey::new_unique(),
+        taker: Pubkey::new_unique(),
+        taker_side: Side::Bids,
+        base_size,
+        quote_size,
+    }
+}
+
+#[test]
+fn fills_are_priced_per_unit_in_sol() {
+    // 500 quote lots of 1,000,000 lamports for 2 units
+    let trade = fill(2, 500).to_trade(&FeeModel::default(), now()).unwrap();
+
+    assert_eq!(trade.amount, Decimal::from(2));
+    assert_eq!(trade.price, dec("0.25"));
+}
+
+#[test]
+fn base_lots_are_scaled_by_the_multiplier() {
+    let fees = FeeModel {
+        base_currency_multiplier: 10,
+        ..FeeModel::default()
+    };
+
+    let trade = fill(2, 500).to_trade(&fees, now()).unwrap();
+
+    assert_eq!(trade.amount, Decimal::from(20));
+    assert_eq!(trade.price, dec("0.025"));
+}
+
+#[test]
+fn empty_fills_are_not_trades() {
+    assert_eq!(fill(0, 0).to_trade(&FeeModel::default(), now()), None);
+}
+
+#[test]
+fn parses_trade_times() {
+    assert_eq!(parse_ts("2022-11-01 00:00:00.000").unwrap().timestamp(), 1_667_260_800);
+    assert_eq!(parse_ts("2022-11-01T01:02:03Z").unwrap().timestamp(), 1_667_264_523);
+    assert_eq!(parse_ts("yesterday"), None);
+}
+
+#[test]
+fn deserializes_trade_times() {
+    let trade: Trade =
+        serde_json::from_str(r#"{"ts": "2022-11-01 12:00:00.000", "amount": "2", "price": "0.25"}"#).unwrap();
+
+    assert_eq!(trade.ts, now());
+}
+
+fn callback_info(byte: u8) -> CallBackInfo {
+    CallBackInfo {
+        user_account: Pubkey::new_from_array([byte; 32]),
+        fee_tier: 0,
+    }
+}
+
+/// Event queue whose first `consumed` fills were already cranked, with fills
+/// as (taker side, base size, quote size)
+fn event_queue(fills: &[(u8, u64, u64)], consumed: u64) -> Vec<u8> {
+    let mut buf = vec![0; EventQueue::<CallBackInfo>::compute_allocation_size(8)];
+    buf[0] = AccountTag::EventQueue as u8;
+
+    let mut event_queue = EventQueue::<CallBackInfo>::from_buffer(&mut buf, AccountTag::EventQueue).unwrap();
+
+    for (index, &(taker_side, base_size, quote_size)) in fills.iter().enumerate() {
+        let event = FillEvent {
+            tag: EventTag::Fill as u8,
+            taker_side,
+            _padding: [0; 6],
+            quote_size,
+            maker_order_id: index as u128,
+            base_size,
+        };
+
+        event_queue
+            .push_back(event, Some(&callback_info(1)), Some(&callback_info(2)))
+            .unwrap();
+    }
+
+    event_queue.pop_n(consumed);
+
+    buf
+}
+
+#[test]
+fn decodes_event_queue_fills() {
+    let mut buf = event_queue(&[(0, 1, 100), (0, 2, 500), (1, 3, 900)], 1);
+
+    let fills = decode_fills(&mut buf).unwrap();
+
+    assert_eq!(fills.len(), 2);
+    assert_eq!(fills[0].seq_num, 1);
+    assert_eq!(fills[0].maker_order_id, 1);
+    assert_eq!(fills[0].maker, Pubkey::new_from_array([1; 32]));
+    assert_eq!(fills[0].taker, Pubkey::new_from_array([2; 32]));
+    assert_eq!(fills[0].taker_side, Side::Bids);
+    assert_eq!((fills[0].base_size, fills[0].quote_size), (2, 500));
+    assert_eq!(fills[1].seq_num, 2);
+    assert_eq!(fills[1].taker_side, Side::Asks);
+}
+
+#[test]
+fn rejects_other_accounts() {
+    let mut buf = event_queue(&[], 0);
+    buf[0] = AccountTag::Asks as u8;
+
+    assert!(decode_fills(&mut buf).is_err());
+}
+
+fn numbered_fill(seq_num: u64, base_size: u64, quote_size: u64) -> Fill {
+    Fill { seq_num, ..fill(base_size, quote_size) }
+}
+
+#[test]
+fn fills_are_stamped_with_the_load_that_found_them() {
+    let fees = FeeModel::default();
+    let first = now();
+    let second = first + Duration::minutes(1);
+    let mut fills = FillTrades::default();
+
+    // Could have been queued any time before
+    fills.load(&[numbered_fill(4, 1, 100)], &fees, first);
+
+    let trades = fills.trades().collect::<Vec<_>>();
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].ts, first);
+    assert!(trades[0].approximate);
+
+    // Still queued, along with two new ones
+    fills.load(
+        &[numbered_fill(4, 1, 100), numbered_fill(5, 2, 500), numbered_fill(6, 1, 300)],
+        &fees,
+        second,
+    );
+
+    let trades = fills.trades().collect::<Vec<_>>();
+    assert_eq!(trades.len(), 3);
+    assert_eq!(trades[0].price, dec("0.3"));
+    assert_eq!(trades[1].price, dec("0.25"));
+    assert_eq!((trades[0].ts, trades[1].ts, trades[2].ts), (second, second, first));
+
+    // Cranked since, the trades are kept
+    fills.load(&[], &fees, second + Duration::minutes(1));
+    assert_eq!(fills.trades().count(), 3);
+}
+
+#[test]
+fn drops_the_trades_the_history_has() {
+    let fees = FeeModel::default();
+    let first = now();
+    let second = first + Duration::minutes(1);
+    let mut fills = FillTrades::default();
+
+    fills.load(&[numbered_fill(0, 3, 900)], &fees, first);
+    fills.load(&[numbered_fill(1, 2, 500), numbered_fill(2, 1, 300)], &fees, second);
+
+    let trade = |ts, amount: i64, price| Trade {
+        ts,
+        amount: Decimal::from(amount),
+        price: dec(price),
+        approximate: false,
+    };
+
+    fills.dedup(&[
+        // The fill of the first load, from whenever before it
+        trade(first - Duration::days(2), 3, "0.3"),
+        // Same trade as the second fill, but from before the first load
+        trade(first - Duration::seconds(1), 2, "0.25"),
+        // Same as the third fill, between the loads
+        trade(first + Duration::seconds(30), 1, "0.3"),
+    ]);
+
+    let trades = fills.trades().collect::<Vec<_>>();
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].amount, Decimal::TWO);
+}
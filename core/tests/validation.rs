@@ -0,0 +1,91 @@
+use magic_eden_sft_core::prices::KI_MINT;
+use magic_eden_sft_core::validation::{validate_recipes, RecipeIssue};
+use magic_eden_sft_core::{genopets_markets, genopets_recipes_json, CURRENCIES};
+use serde_json::json;
+use std::collections::HashSet;
+
+fn items(items: &[&str]) -> HashSet<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+#[test]
+fn currencies_include_the_ki_mint() {
+    assert_eq!(CURRENCIES, [KI_MINT, "GENE"]);
+}
+
+#[test]
+fn bundled_recipes_are_valid() {
+    let items = genopets_markets()
+        .into_iter()
+        .map(|item| item.token_address)
+        .collect();
+
+    assert_eq!(validate_recipes(&genopets_recipes_json(), &items, &CURRENCIES), vec![]);
+}
+
+#[test]
+fn reports_unknown_reagents_and_unreachable_items() {
+    let data = json!({
+        "baseCosts": {},
+        "recipes": {
+            "a": { "GENE": 1, "ghost": 1 },
+            "orphan": { "GENE": 1 }
+        }
+    });
+
+    assert_eq!(
+        validate_recipes(&data, &items(&["a"]), &CURRENCIES),
+        vec![
+            RecipeIssue::UnknownReagent {
+                item: String::from("a"),
+                reagent: String::from("ghost"),
+            },
+            RecipeIssue::UnreachableItem(String::from("orphan")),
+        ]
+    );
+}
+
+#[test]
+fn reports_string_and_negative_amounts() {
+    let data = json!({
+        "baseCosts": { "crystal": { "GENE": "10" } },
+        "recipes": { "a": { "crystal": -1 } }
+    });
+
+    let issues = validate_recipes(&data, &items(&["a", "crystal"]), &CURRENCIES);
+
+    assert_eq!(issues.len(), 2);
+    assert!(issues.iter().all(|issue| matches!(issue, RecipeIssue::AmountType { .. })));
+}
+
+#[test]
+fn reports_cycles_and_duplicate_costs() {
+    let data = json!({
+        "baseCosts": { "c": { "GENE": 1 } },
+        "recipes": {
+            "a": { "b": 1 },
+            "b": { "c": 1 },
+            "c": { "a": 1 }
+        }
+    });
+
+    assert_eq!(
+        validate_recipes(&data, &items(&["a", "b", "c"]), &CURRENCIES),
+        vec![
+            RecipeIssue::DuplicateCost(String::from("c")),
+            RecipeIssue::Cycle(vec![
+                String::from("a"),
+                String::from("b"),
+                String::from("c"),
+                String::from("a"),
+            ]),
+        ]
+    );
+}
+
+#[test]
+fn rejects_malformed_files() {
+    let issues = validate_recipes(&json!({ "recipes": {} }), &items(&[]), &CURRENCIES);
+
+    assert_eq!(issues, vec![RecipeIssue::Malformed(String::from("baseCosts should be an object"))]);
+}
@@ -0,0 +1,86 @@
+use crate::currency::DisplayCurrency;
+use magic_eden_sft_core::CraftOpportunity;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ArbitrageReportProps {
+    pub opportunities: Vec<CraftOpportunity>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SortBy {
+    MarginSol,
+    MarginPercent,
+}
+
+/// Items that can be crafted for less than their floor, reagents crafted or
+/// bought at the floor, whichever is cheaper
+#[function_component(ArbitrageReport)]
+pub fn arbitrage_report(props: &ArbitrageReportProps) -> Html {
+    let sort = use_state(|| (SortBy::MarginSol, true));
+    let currency = use_context::<DisplayCurrency>().unwrap_or_default();
+
+    let mut opportunities = props.opportunities.clone();
+    let (sort_by, descending) = *sort;
+    opportunities.sort_by(|a, b| {
+        let ordering = match sort_by {
+            SortBy::MarginSol => a.margin_sol.cmp(&b.margin_sol),
+            SortBy::MarginPercent => a.margin_percent.cmp(&b.margin_percent),
+        };
+
+        match descending {
+            true => ordering.reverse(),
+            false => ordering,
+        }
+    });
+
+    let header = |label: &str, column: SortBy| {
+        let sort = sort.clone();
+        let arrow = match *sort {
+            (sort_by, true) if sort_by == column => " ▼",
+            (sort_by, false) if sort_by == column => " ▲",
+            _ => "",
+        };
+        let onclick = Callback::from(move |_| {
+            let (sort_by, descending) = *sort;
+
+            // Clicking the sorted column flips the direction
+            sort.set((column, sort_by != column || !descending));
+        });
+
+        html!(<th style="cursor: pointer" { onclick }>{ label }{ arrow }</th>)
+    };
+
+    let rows = opportunities.iter().map(|opportunity| {
+        html!(<tr key={ opportunity.token_address.clone() }>
+            <td>
+                <a href={ format!("https://magiceden.io/sft/{}", opportunity.market_address) } target="_blank">
+                    { &opportunity.token_title }
+                </a>
+            </td>
+            <td>{ currency.amount(opportunity.production_cost, 4) }</td>
+            <td>{ currency.amount(opportunity.floor, 4) }</td>
+            <td>{ currency.amount(opportunity.margin_sol, 4) }</td>
+            <td>{ format!("{}%", opportunity.margin_percent.round_dp(1)) }</td>
+        </tr>)
+    });
+
+    html!(<details class="mb-3">
+        <summary>{ format!("Craft and sell opportunities ({})", opportunities.len()) }</summary>
+        <div class="form-text">{ "Floor is what listing at the current ask floor pays out. Production cost crafts or buys every reagent, whichever is cheaper." }</div>
+        <table class="table table-sm table-striped table-bordered mt-2">
+            <thead>
+                <tr>
+                    <th>{ "Item" }</th>
+                    <th>{ format!("Production cost ({})", currency.symbol()) }</th>
+                    <th>{ format!("Floor ({})", currency.symbol()) }</th>
+                    { header(&format!("Margin ({})", currency.symbol()), SortBy::MarginSol) }
+                    { header("Margin (%)", SortBy::MarginPercent) }
+                </tr>
+            </thead>
+            <tbody>
+                { for rows }
+            </tbody>
+        </table>
+    </details>)
+}
@@ -0,0 +1,101 @@
+use crate::currency::DisplayCurrency;
+use magic_eden_sft_core::CollectionOverview;
+use rust_decimal::Decimal;
+use yew::prelude::*;
+
+/// Collections of the market registry, with their display names
+pub const COLLECTIONS: [(&str, &str); 7] = [
+    ("genopets_augments", "Augment"),
+    ("genopets_cosmetics", "Cosmetic"),
+    ("genopets_genotype_crystals", "Crystal"),
+    ("genopets_power_ups", "Power up"),
+    ("genopets_reagents", "Reagent"),
+    ("genopets_recipe_hunt", "Recipe hunt missing page"),
+    ("genopets_terraform_seeds_sft", "Terraform seed"),
+];
+
+pub fn collection_label(collection: &str) -> &str {
+    COLLECTIONS
+        .iter()
+        .find(|(value, _)| *value == collection)
+        .map_or(collection, |(_, label)| *label)
+}
+
+#[derive(Properties, PartialEq)]
+pub struct CollectionDashboardProps {
+    pub overviews: Vec<CollectionOverview>,
+    /// Filters the item table by the collection
+    pub onselect: Callback<String>,
+}
+
+/// Listings and trading activity of every collection at a glance
+#[function_component(CollectionDashboard)]
+pub fn collection_dashboard(props: &CollectionDashboardProps) -> Html {
+    let currency = use_context::<DisplayCurrency>().unwrap_or_default();
+
+    let fmt_price = |price: Option<Decimal>| match price {
+        Some(price) => currency.amount(price, 3),
+        None => String::from("-"),
+    };
+
+    let rows = props.overviews.iter().map(|overview| {
+        let onclick = {
+            let onselect = props.onselect.clone();
+            let collection = overview.collection.clone();
+
+            Callback::from(move |e: MouseEvent| {
+                e.prevent_default();
+                onselect.emit(collection.clone());
+            })
+        };
+
+        let movers = overview.movers.iter().map(|mover| {
+            let class = match mover.change_percent.is_sign_negative() {
+                true => "text-danger",
+                false => "text-success",
+            };
+
+            html!(<div key={ mover.token_address.clone() }>
+                <a href={ format!("https://magiceden.io/sft/{}", mover.market_address) } target="_blank">
+                    { &mover.token_title }
+                </a>
+                <span class={ classes!("ms-1", class) }>{ format!("{}%", mover.change_percent.round_dp(1)) }</span>
+            </div>)
+        });
+
+        html!(<tr key={ overview.collection.clone() }>
+            <td><a href="#" { onclick }>{ collection_label(&overview.collection) }</a></td>
+            <td>{ overview.items }</td>
+            <td>{ overview.listed_units }</td>
+            <td>{ fmt_price(overview.floor) }</td>
+            <td>{ fmt_price(overview.median_ask) }</td>
+            <td>{ currency.amount(overview.volume_24h, 3) }</td>
+            <td>{ currency.amount(overview.volume_7d, 3) }</td>
+            <td class="small">{ for movers }</td>
+        </tr>)
+    });
+
+    let symbol = currency.symbol();
+
+    html!(<details class="mb-3" open=true>
+        <summary>{ "Collections" }</summary>
+        <div class="form-text">{ "Asks include fees. Movers compare the last 24h VWAP against the day before. Click a collection to filter the items below." }</div>
+        <table class="table table-sm table-striped table-bordered mt-2">
+            <thead>
+                <tr>
+                    <th>{ "Collection" }</th>
+                    <th>{ "Items" }</th>
+                    <th>{ "Listed units" }</th>
+                    <th>{ format!("Floor ({symbol})") }</th>
+                    <th>{ format!("Median ask ({symbol})") }</th>
+                    <th>{ format!("24h volume ({symbol})") }</th>
+                    <th>{ format!("7d volume ({symbol})") }</th>
+                    <th>{ "Movers (24h)" }</th>
+                </tr>
+            </thead>
+            <tbody>
+                { for rows }
+            </tbody>
+        </table>
+    </details>)
+}
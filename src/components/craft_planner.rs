@@ -0,0 +1,189 @@
+use crate::currency::DisplayCurrency;
+use magic_eden_sft_core::{CraftPlan, PlanLine, ShoppingCost};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct CraftPlannerProps {
+    /// Items with a recipe, as token address and title
+    pub craftable: Vec<(String, String)>,
+    /// Title of every known item, by token address
+    pub titles: HashMap<String, String>,
+    pub targets: Vec<(String, u64)>,
+    pub plan: CraftPlan,
+    /// `None` until KI and GENE prices are loaded
+    pub shopping: Option<ShoppingCost>,
+    /// Whether holdings of an owner are being subtracted
+    pub has_owner: bool,
+    pub ontargets: Callback<Vec<(String, u64)>>,
+}
+
+/// Bill of materials for crafting a batch of items, with what's left to buy
+/// priced against the asks
+#[function_component(CraftPlanner)]
+pub fn craft_planner(props: &CraftPlannerProps) -> Html {
+    let item = use_node_ref();
+    let quantity = use_node_ref();
+    let currency = use_context::<DisplayCurrency>().unwrap_or_default();
+
+    let fmt = |value: Decimal| value.round_dp(4).normalize().to_string();
+    let title = |token_address: &str| {
+        props
+            .titles
+            .get(token_address)
+            .cloned()
+            .unwrap_or_else(|| token_address.to_string())
+    };
+
+    let onadd = {
+        let item = item.clone();
+        let quantity = quantity.clone();
+        let targets = props.targets.clone();
+        let ontargets = props.ontargets.clone();
+
+        Callback::from(move |_| {
+            let token_address = item.cast::<HtmlSelectElement>().unwrap().value();
+            let quantity = quantity
+                .cast::<HtmlInputElement>()
+                .unwrap()
+                .value()
+                .parse::<u64>()
+                .unwrap_or_default();
+            if token_address.is_empty() || quantity == 0 {
+                return;
+            }
+
+            // Adding an item again adds to its quantity
+            let mut targets = targets.clone();
+            match targets.iter_mut().find(|(target, _)| *target == token_address) {
+                Some((_, target_quantity)) => *target_quantity += quantity,
+                None => targets.push((token_address, quantity)),
+            }
+
+            ontargets.emit(targets);
+        })
+    };
+
+    let options = props.craftable.iter().map(|(token_address, title)| {
+        html!(<option value={ token_address.clone() }>{ title }</option>)
+    });
+
+    let targets = props.targets.iter().enumerate().map(|(index, (token_address, quantity))| {
+        let targets = props.targets.clone();
+        let ontargets = props.ontargets.clone();
+        let onclick = Callback::from(move |_| {
+            let mut targets = targets.clone();
+            targets.remove(index);
+
+            ontargets.emit(targets);
+        });
+
+        html!(<li key={ token_address.clone() }>
+            { format!("{quantity} × {}", title(token_address)) }
+            <button class="btn btn-link btn-sm" { onclick }>{ "Remove" }</button>
+        </li>)
+    });
+
+    let row = |name: String, line: &PlanLine, cost: Html| {
+        html!(<tr>
+            <td>{ name }</td>
+            <td>{ fmt(line.needed) }</td>
+            <td>{ fmt(line.held) }</td>
+            <td>{ fmt(line.crafted) }</td>
+            <td>{ fmt(line.to_buy) }</td>
+            <td>{ cost }</td>
+        </tr>)
+    };
+
+    let sol = |sol: Decimal| html!({ currency.format(sol, 4) });
+    let shopping = props.shopping.as_ref();
+
+    let currencies = [
+        ("KI", &props.plan.ki, shopping.map(|shopping| shopping.ki_sol)),
+        ("GENE", &props.plan.gene, shopping.map(|shopping| shopping.gene_sol)),
+    ]
+    .into_iter()
+    .filter(|(_, line, _)| line.needed > Decimal::ZERO)
+    .map(|(name, line, cost)| row(name.to_string(), line, cost.map(sol).unwrap_or_default()));
+
+    let items = props.plan.items.iter().map(|(token_address, line)| {
+        let quote = shopping.and_then(|shopping| shopping.items.get(token_address));
+        let cost = match quote {
+            None => html!(),
+            Some(None) => html!(<span class="text-muted">{ "No orderbook" }</span>),
+            Some(Some(quote)) if quote.has_enough_depth() => sol(quote.total_sol),
+            Some(Some(quote)) => html!(<>
+                { sol(quote.total_sol) }
+                <span class="text-danger ms-1">
+                    { format!("(only {} of {} listed)", quote.filled_quantity, quote.requested_quantity) }
+                </span>
+            </>),
+        };
+
+        row(title(token_address), line, cost)
+    });
+
+    let total = shopping.map(|shopping| {
+        let warning = (!shopping.complete).then(|| {
+            html!(<span class="text-danger ms-1">{ "(not enough listed to buy everything)" }</span>)
+        });
+
+        html!(<p><b>{ "Shopping list total: " }</b>{ sol(shopping.total_sol) }{ warning }</p>)
+    });
+
+    let cycles = props.plan.cycles.iter().map(|token_address| {
+        html!(<li class="text-danger">
+            { format!("{} needs itself to be crafted, it's bought instead", title(token_address)) }
+        </li>)
+    });
+
+    let holdings_note = match props.has_owner {
+        true => "Holdings of the owner are used first, except for the targets themselves.",
+        false => "Enter an owner address to subtract what it already holds.",
+    };
+
+    html!(<details class="mb-3">
+        <summary>{ format!("Crafting planner ({})", props.targets.len()) }</summary>
+        <div class="form-text">{ holdings_note }</div>
+        <div class="row g-2 mt-1">
+            <div class="col-md-6">
+                <select class="form-select form-select-sm" ref={ item }>
+                    { for options }
+                </select>
+            </div>
+            <div class="col-md-3">
+                <input class="form-control form-control-sm" type="number" min="1" placeholder="Units" ref={ quantity } />
+            </div>
+            <div class="col-md-3">
+                <button class="btn btn-primary btn-sm" onclick={ onadd }>{ "Add" }</button>
+            </div>
+        </div>
+        <ul class="list-unstyled small mt-2">
+            { for targets }
+        </ul>
+        if !props.targets.is_empty() {
+            <table class="table table-sm table-striped table-bordered">
+                <thead>
+                    <tr>
+                        <th>{ "Ingredient" }</th>
+                        <th>{ "Needed" }</th>
+                        <th>{ "Held" }</th>
+                        <th>{ "Crafted" }</th>
+                        <th>{ "To buy" }</th>
+                        <th>{ "Cost" }</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for currencies }
+                    { for items }
+                </tbody>
+            </table>
+            <ul class="list-unstyled small">
+                { for cycles }
+            </ul>
+            { total }
+        }
+    </details>)
+}
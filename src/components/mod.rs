@@ -1,3 +1,11 @@
+pub mod arbitrage_report;
+pub mod collection_dashboard;
+pub mod craft_planner;
 pub mod open_orders;
 pub mod pagination;
+pub mod price_chart;
+pub mod recipe_tree;
+pub mod rpc_settings;
+pub mod status_bar;
+pub mod sweep_calculator;
 pub mod trade_summary;
@@ -1,55 +1,178 @@
-use crate::utils::Listing;
-use rust_decimal::{Decimal, MathematicalOps};
+use crate::currency::DisplayCurrency;
+use magic_eden_sft_core::{FeeModel, Listing, MarketOrders, OrderChange, OrderChanges, PriceLevel, Side};
+use rust_decimal::Decimal;
 use solana_sdk::pubkey::Pubkey;
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct OpenOrdersProps {
-    pub orders: Vec<Listing>,
+    pub orders: MarketOrders,
+    pub fees: FeeModel,
     pub owner_key: Option<Pubkey>,
+    /// Recent changes, highlighted until they expire
+    #[prop_or_default]
+    pub changes: OrderChanges,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OrdersView {
+    Individual,
+    Depth,
 }
 
 #[function_component(OpenOrders)]
 pub fn open_orders(props: &OpenOrdersProps) -> Html {
-    let orders = props.orders.iter().filter_map(|listing| {
-        match props.owner_key {
-            Some(owner_key) if owner_key != listing.owner => return None,
-            _ => {}
-        }
+    let view = use_state(|| OrdersView::Individual);
+    let currency = use_context::<DisplayCurrency>().unwrap_or_default();
+    let fees = props.fees;
+    let best_bid = props.orders.best_bid().map(|listing| fees.seller_receives(listing.price));
+    let best_ask = props.orders.best_ask().map(|listing| fees.buyer_pays(listing.price));
 
-        let price = compute_ui_price(listing.price);
+    let spread = match (best_bid, best_ask) {
+        (Some(best_bid), Some(best_ask)) => currency.format(best_ask - best_bid, 3),
+        _ => String::from("-"),
+    };
 
-        Some(html!(<tr key={ listing.key }>
-            <td>{ price.round_dp(3).to_string() }</td>
-            <td>{ listing.base_quantity }</td>
-        </tr>))
-    });
+    let fmt_price = |price: Option<Decimal>| match price {
+        Some(price) => currency.format(price, 3),
+        None => String::from("-"),
+    };
+
+    let changes = &props.changes;
+    let row_class = |listing: &Listing| match changes.changed.get(&listing.key) {
+        Some(OrderChange::New) => "table-success",
+        Some(OrderChange::Repriced) | Some(OrderChange::Resized) => "table-warning",
+        None => "",
+    };
+    let removed_class = |_: &Listing| "table-danger text-decoration-line-through";
+
+    // Best prices go on top for both sides, followed by the recently removed
+    // orders
+    let bids = order_rows(props.orders.bids.iter().rev(), props.owner_key, row_class, currency, move |price| {
+        fees.seller_receives(price)
+    })
+    .chain(order_rows(changes.removed.bids.iter().rev(), props.owner_key, removed_class, currency, move |price| {
+        fees.seller_receives(price)
+    }));
+    let asks = order_rows(props.orders.asks.iter(), props.owner_key, row_class, currency, move |price| {
+        fees.buyer_pays(price)
+    })
+    .chain(order_rows(changes.removed.asks.iter(), props.owner_key, removed_class, currency, move |price| {
+        fees.buyer_pays(price)
+    }));
+
+    let toggle = [(OrdersView::Individual, "Orders"), (OrdersView::Depth, "Depth")]
+        .into_iter()
+        .map(|(option, label)| {
+            let view = view.clone();
+            let class = match *view == option {
+                true => "btn btn-secondary",
+                false => "btn btn-outline-secondary",
+            };
+            let onclick = Callback::from(move |_| view.set(option));
+
+            html!(<button type="button" { class } { onclick }>{ label }</button>)
+        });
+
+    let summary = html!(<>
+        <b>{ "Best bid: " }</b>{ fmt_price(best_bid) }<br/>
+        <b>{ "Best ask: " }</b>{ fmt_price(best_ask) }<br/>
+        <b>{ "Spread: " }</b>{ spread }<br/>
+        <div class="btn-group btn-group-sm my-1">
+            { for toggle }
+        </div>
+    </>);
+
+    if *view == OrdersView::Depth {
+        let asks = props.orders.price_levels(Side::Asks, &fees);
+        let bids = props.orders.price_levels(Side::Bids, &fees);
+
+        return html!(<div style="height: 250px; overflow: auto">
+            { summary }
+            <h6>{ "Asks" }</h6>
+            { depth_ladder(&asks, currency, |price| fees.buyer_pays(price)) }
+            <h6>{ "Bids" }</h6>
+            { depth_ladder(&bids, currency, |price| fees.seller_receives(price)) }
+        </div>);
+    }
 
     html!(<div style="height: 250px; overflow: auto">
+        { summary }
+        <h6>{ "Asks" }</h6>
+        <table class="table table-striped table-bordered">
+            <thead>
+                <tr>
+                    <th>{ format!("Price ({})", currency.symbol()) }</th>
+                    <th>{ "Quantity" }</th>
+                </tr>
+            </thead>
+            <tbody>
+                { for asks }
+            </tbody>
+        </table>
+        <h6>{ "Bids" }</h6>
         <table class="table table-striped table-bordered">
             <thead>
                 <tr>
-                    <th>{ "Price" }</th>
+                    <th>{ format!("Price ({})", currency.symbol()) }</th>
                     <th>{ "Quantity" }</th>
                 </tr>
             </thead>
             <tbody>
-                { for orders }
+                { for bids }
             </tbody>
         </table>
     </div>)
 }
 
-fn compute_ui_price(price: u64) -> Decimal {
-    // This is a weitd number. I would've expected it to be 9496 (5% + 0.04%)
-    let fee_mult = Decimal::from_i128_with_scale(9520, 4);
+fn order_rows<'a>(
+    orders: impl Iterator<Item = &'a Listing> + 'a,
+    owner_key: Option<Pubkey>,
+    row_class: impl Fn(&Listing) -> &'static str + 'a,
+    currency: DisplayCurrency,
+    ui_price: impl Fn(u64) -> Decimal + 'a,
+) -> impl Iterator<Item = Html> + 'a {
+    orders.filter_map(move |listing| {
+        match owner_key {
+            Some(owner_key) if owner_key != listing.owner => return None,
+            _ => {}
+        }
+
+        let price = ui_price(listing.price);
+        let class = row_class(listing);
 
-    let quote_currency_multiplier = Decimal::from_i128_with_scale(1_000_000, 0);
-    let price = Decimal::from_i128_with_scale(price as i128, 0);
+        Some(html!(<tr key={ listing.key } { class }>
+            <td>{ currency.amount(price, 3) }</td>
+            <td>{ listing.base_quantity }</td>
+        </tr>))
+    })
+}
 
-    let numerator = price * quote_currency_multiplier;
-    let denominator =
-        Decimal::from_i128_with_scale(1_000_000_000, 0) * Decimal::TWO.powu(32) * fee_mult;
+/// Price levels, best first, with the quantity and SOL needed to take all of
+/// them up to each one
+fn depth_ladder(levels: &[PriceLevel], currency: DisplayCurrency, ui_price: impl Fn(u64) -> Decimal) -> Html {
+    let rows = levels.iter().map(|level| {
+        html!(<tr key={ level.price }>
+            <td>{ currency.amount(ui_price(level.price), 3) }</td>
+            <td>{ level.base_quantity }</td>
+            <td>{ level.order_count }</td>
+            <td>{ level.cumulative_quantity }</td>
+            <td>{ currency.amount(level.cumulative_sol, 3) }</td>
+        </tr>)
+    });
 
-    numerator / denominator
+    html!(<table class="table table-striped table-bordered">
+        <thead>
+            <tr>
+                <th>{ format!("Price ({})", currency.symbol()) }</th>
+                <th>{ "Quantity" }</th>
+                <th>{ "Orders" }</th>
+                <th>{ "Cum. quantity" }</th>
+                <th>{ format!("Cum. {}", currency.symbol()) }</th>
+            </tr>
+        </thead>
+        <tbody>
+            { for rows }
+        </tbody>
+    </table>)
 }
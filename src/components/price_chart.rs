@@ -0,0 +1,132 @@
+use crate::currency::DisplayCurrency;
+use magic_eden_sft_core::{candles, CandleInterval, Trade};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use yew::prelude::*;
+
+const WIDTH: f64 = 300.0;
+const HEIGHT: f64 = 150.0;
+/// Bottom part of the chart taken by the volume bars
+const VOLUME_HEIGHT: f64 = 30.0;
+/// Most recent candles shown
+const MAX_CANDLES: usize = 40;
+
+#[derive(Properties, PartialEq)]
+pub struct PriceChartProps {
+    pub trades: Vec<Trade>,
+    /// Current ask floor, drawn as a line across the chart
+    pub floor: Option<Decimal>,
+}
+
+/// Candlestick chart of the trades with their volume, in the display currency
+#[function_component(PriceChart)]
+pub fn price_chart(props: &PriceChartProps) -> Html {
+    let interval = use_state(|| CandleInterval::Day);
+    let currency = use_context::<DisplayCurrency>().unwrap_or_default();
+
+    let mut candles = candles(&props.trades, *interval);
+    let older = candles.len().saturating_sub(MAX_CANDLES);
+    candles.drain(..older);
+
+    let toggle = CandleInterval::ALL.into_iter().map(|option| {
+        let interval = interval.clone();
+        let class = match *interval == option {
+            true => "btn btn-secondary",
+            false => "btn btn-outline-secondary",
+        };
+        let onclick = Callback::from(move |_| interval.set(option));
+
+        html!(<button type="button" { class } { onclick }>{ option.label() }</button>)
+    });
+
+    let toggle = html!(<div class="btn-group btn-group-sm my-1">{ for toggle }</div>);
+
+    if candles.is_empty() {
+        return toggle;
+    }
+
+    let to_f64 = |value: Decimal| value.to_f64().unwrap_or_default();
+    let low = candles.iter().map(|candle| candle.low).chain(props.floor).min().unwrap_or_default();
+    let high = candles.iter().map(|candle| candle.high).chain(props.floor).max().unwrap_or_default();
+    let max_volume = candles.iter().map(|candle| to_f64(candle.volume)).fold(0.0, f64::max);
+
+    let price_height = HEIGHT - VOLUME_HEIGHT;
+    // Flat charts are drawn across the middle
+    let range = match to_f64(high - low) {
+        range if range > 0.0 => range,
+        _ => 1.0,
+    };
+    let y = |price: Decimal| (to_f64(high - price) / range) * (price_height - 10.0) + 5.0;
+
+    let slot = WIDTH / candles.len() as f64;
+    let body_width = (slot * 0.6).max(1.0);
+
+    let bars = candles.iter().enumerate().map(|(index, candle)| {
+        let center = slot * (index as f64 + 0.5);
+        let color = match candle.close >= candle.open {
+            true => "#198754",
+            false => "#dc3545",
+        };
+
+        let body_top = y(candle.open.max(candle.close));
+        let body_height = (y(candle.open.min(candle.close)) - body_top).max(1.0);
+        let volume_height = match max_volume > 0.0 {
+            true => to_f64(candle.volume) / max_volume * (VOLUME_HEIGHT - 2.0),
+            false => 0.0,
+        };
+
+        let title = format!(
+            "{}\nO {} H {} L {} C {}\nVWAP {}\nVolume {}",
+            candle.start.format("%Y-%m-%d %H:%M UTC"),
+            currency.amount(candle.open, 4),
+            currency.amount(candle.high, 4),
+            currency.amount(candle.low, 4),
+            currency.amount(candle.close, 4),
+            currency.format(candle.vwap, 4),
+            candle.volume,
+        );
+
+        html!(<g key={ candle.start.to_string() }>
+            <title>{ title }</title>
+            <line
+                x1={ center.to_string() } x2={ center.to_string() }
+                y1={ y(candle.high).to_string() } y2={ y(candle.low).to_string() }
+                stroke={ color }
+            />
+            <rect
+                x={ (center - body_width / 2.0).to_string() } y={ body_top.to_string() }
+                width={ body_width.to_string() } height={ body_height.to_string() }
+                fill={ color }
+            />
+            <rect
+                x={ (center - body_width / 2.0).to_string() } y={ (HEIGHT - volume_height).to_string() }
+                width={ body_width.to_string() } height={ volume_height.to_string() }
+                fill="#adb5bd"
+            />
+        </g>)
+    });
+
+    let floor = props.floor.map(|floor| {
+        let floor_y = y(floor).to_string();
+
+        html!(<g>
+            <title>{ format!("Ask floor {}", currency.format(floor, 4)) }</title>
+            <line
+                x1="0" x2={ WIDTH.to_string() } y1={ floor_y.clone() } y2={ floor_y }
+                stroke="#0d6efd" stroke-dasharray="4 2"
+            />
+        </g>)
+    });
+
+    html!(<div>
+        { toggle }
+        <div class="small text-muted">
+            { format!("{} – {}", currency.format(low, 4), currency.format(high, 4)) }
+        </div>
+        <svg viewBox={ format!("0 0 {WIDTH} {HEIGHT}") } width="100%" style="max-width: 300px">
+            { for bars }
+            { floor }
+        </svg>
+    </div>)
+}
+
@@ -0,0 +1,61 @@
+use crate::currency::DisplayCurrency;
+use magic_eden_sft_core::{Acquisition, CostNode};
+use rust_decimal::Decimal;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct RecipeTreeProps {
+    pub root: CostNode,
+}
+
+/// Recipe of an item, expandable down to its base ingredients, with what each
+/// node costs and whether it's cheaper to craft or buy
+#[function_component(RecipeTree)]
+pub fn recipe_tree(props: &RecipeTreeProps) -> Html {
+    let currency = use_context::<DisplayCurrency>().unwrap_or_default();
+
+    html!(<ul class="list-unstyled small">
+        { for props.root.children.iter().map(|child| node(child, currency)) }
+    </ul>)
+}
+
+fn node(node: &CostNode, currency: DisplayCurrency) -> Html {
+    let fmt = |value: Decimal| value.round_dp(4).normalize().to_string();
+
+    let decision = match node.decision {
+        Some(Acquisition::Craft) => html!(<span class="badge bg-primary ms-1">{ "craft" }</span>),
+        Some(Acquisition::Buy) => html!(<span class="badge bg-success ms-1">{ "buy" }</span>),
+        None => html!(),
+    };
+
+    let sol = node
+        .sol
+        .map(|sol| currency.format(sol, 4))
+        .unwrap_or_else(|| format!("- {}", currency.symbol()));
+    let label = html!(<>
+        { format!("{} {}", fmt(node.amount), node.title) }
+        { decision }
+        <span class="text-muted ms-1">
+            { format!("{} KI / {} GENE / {}", fmt(node.ki), fmt(node.gene), sol) }
+        </span>
+    </>);
+
+    if node.children.is_empty() {
+        return html!(<li>{ label }</li>);
+    }
+
+    // Subtrees of bought items are only there for reference
+    let class = match node.decision {
+        Some(Acquisition::Buy) => "ps-3 opacity-50",
+        _ => "ps-3",
+    };
+
+    html!(<li>
+        <details>
+            <summary>{ label }</summary>
+            <ul class={ classes!("list-unstyled", class) }>
+                { for node.children.iter().map(|child| self::node(child, currency)) }
+            </ul>
+        </details>
+    </li>)
+}
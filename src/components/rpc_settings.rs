@@ -0,0 +1,74 @@
+use crate::rpc::{parse_endpoints, EndpointHealth, RpcEndpoint};
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct RpcSettingsProps {
+    pub endpoints: Vec<RpcEndpoint>,
+    pub onsave: Callback<Vec<String>>,
+}
+
+#[function_component(RpcSettings)]
+pub fn rpc_settings(props: &RpcSettingsProps) -> Html {
+    let textarea = use_node_ref();
+
+    let value = props
+        .endpoints
+        .iter()
+        .map(|endpoint| endpoint.url.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let onclick = {
+        let textarea = textarea.clone();
+        let onsave = props.onsave.clone();
+
+        Callback::from(move |_| {
+            let urls = textarea.cast::<HtmlTextAreaElement>().unwrap().value();
+
+            onsave.emit(parse_endpoints(&urls, '\n'));
+        })
+    };
+
+    let endpoints = props.endpoints.iter().map(|endpoint| {
+        let (status, latency) = match &endpoint.health {
+            EndpointHealth::Unknown => (html!({ "-" }), String::from("-")),
+            EndpointHealth::Ok { latency_ms } => (
+                html!(<span class="text-success">{ "OK" }</span>),
+                format!("{latency_ms} ms"),
+            ),
+            EndpointHealth::Failed(reason) => (
+                html!(<span class="text-danger">{ format!("Failed: {reason}") }</span>),
+                String::from("-"),
+            ),
+        };
+
+        html!(<tr key={ endpoint.url.clone() }>
+            <td>{ &endpoint.url }</td>
+            <td>{ status }</td>
+            <td>{ latency }</td>
+        </tr>)
+    });
+
+    html!(<details class="mb-3">
+        <summary>{ "RPC settings" }</summary>
+        <div class="form-group">
+            <label class="form-label">{ "Endpoints" }</label>
+            <textarea class="form-control" ref={ textarea } rows="3" { value } />
+            <div class="form-text">{ "One URL per line. Requests go round-robin, falling back to the next endpoint on failure. The rpc query parameter (comma separated) overrides this list." }</div>
+            <button class="btn btn-primary btn-sm mt-2" { onclick }>{ "Save" }</button>
+        </div>
+        <table class="table table-sm table-bordered mt-2">
+            <thead>
+                <tr>
+                    <th>{ "Endpoint" }</th>
+                    <th>{ "Status" }</th>
+                    <th>{ "Latency" }</th>
+                </tr>
+            </thead>
+            <tbody>
+                { for endpoints }
+            </tbody>
+        </table>
+    </details>)
+}
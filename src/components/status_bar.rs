@@ -0,0 +1,72 @@
+use magic_eden_sft_core::FetchError;
+use yew::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DataSource {
+    Markets,
+    Trades,
+    TokenPrices,
+    UserAccounts,
+    Holdings,
+}
+
+impl DataSource {
+    fn label(&self) -> &'static str {
+        match self {
+            DataSource::Markets => "Orderbooks",
+            DataSource::Trades => "Trade history (Flipside)",
+            DataSource::TokenPrices => "KI/GENE/USD prices",
+            DataSource::UserAccounts => "Owner accounts",
+            DataSource::Holdings => "Owner holdings",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SourceStatus {
+    /// Optionally with the number of loaded and total chunks
+    Loading(Option<(usize, usize)>),
+    Ok,
+    Failed(FetchError),
+}
+
+#[derive(Properties, PartialEq)]
+pub struct StatusBarProps {
+    pub statuses: Vec<(DataSource, SourceStatus)>,
+    pub onretry: Callback<DataSource>,
+}
+
+#[function_component(StatusBar)]
+pub fn status_bar(props: &StatusBarProps) -> Html {
+    let statuses = props.statuses.iter().map(|(source, status)| {
+        let source = *source;
+
+        let (class, text, retry) = match status {
+            SourceStatus::Loading(None) => ("badge bg-secondary", String::from("loading"), None),
+            SourceStatus::Loading(Some((loaded, total))) => (
+                "badge bg-secondary",
+                format!("loading: chunk {loaded}/{total} loaded"),
+                None,
+            ),
+            SourceStatus::Ok => ("badge bg-success", String::from("ok"), None),
+            SourceStatus::Failed(e) => {
+                let onretry = props.onretry.clone();
+                let onclick = Callback::from(move |_| onretry.emit(source));
+
+                let retry = html!(<button class="btn btn-link btn-sm" { onclick }>{ "Retry" }</button>);
+
+                ("badge bg-danger", format!("failed: {e}"), Some(retry))
+            }
+        };
+
+        html!(<span class="me-3">
+            { source.label() }{ " " }
+            <span { class }>{ text }</span>
+            { retry }
+        </span>)
+    });
+
+    html!(<div class="mb-3">
+        { for statuses }
+    </div>)
+}
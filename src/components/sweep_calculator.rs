@@ -0,0 +1,57 @@
+use crate::currency::DisplayCurrency;
+use magic_eden_sft_core::{FeeModel, MarketOrders};
+use rust_decimal::Decimal;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct SweepCalculatorProps {
+    pub orders: MarketOrders,
+    pub fees: FeeModel,
+}
+
+/// Cost of buying a number of units off the asks, fees included
+#[function_component(SweepCalculator)]
+pub fn sweep_calculator(props: &SweepCalculatorProps) -> Html {
+    let quantity = use_state(|| 0u64);
+    let currency = use_context::<DisplayCurrency>().unwrap_or_default();
+
+    let oninput = {
+        let quantity = quantity.clone();
+
+        Callback::from(move |e: InputEvent| {
+            let value = e.target_unchecked_into::<HtmlInputElement>().value();
+
+            quantity.set(value.parse().unwrap_or_default());
+        })
+    };
+
+    let result = (*quantity > 0).then(|| {
+        let quote = props.orders.sweep(*quantity, &props.fees);
+
+        let fmt_price = |price: Option<Decimal>| match price {
+            Some(price) => currency.format(price, 4),
+            None => String::from("-"),
+        };
+
+        let depth = match quote.has_enough_depth() {
+            true => html!(<span class="text-success">{ "Enough depth" }</span>),
+            false => html!(<span class="text-danger">
+                { format!("Only {} of {} listed", quote.filled_quantity, quote.requested_quantity) }
+            </span>),
+        };
+
+        html!(<div class="small">
+            <b>{ "Total: " }</b>{ fmt_price(Some(quote.total_sol)) }<br/>
+            <b>{ "Average: " }</b>{ fmt_price(quote.average_price) }<br/>
+            <b>{ "Worst: " }</b>{ fmt_price(quote.worst_price) }<br/>
+            { depth }
+        </div>)
+    });
+
+    html!(<div class="mt-2">
+        <label class="form-label small">{ "Cost to buy" }</label>
+        <input class="form-control form-control-sm" type="number" min="0" placeholder="Units" { oninput } />
+        { result }
+    </div>)
+}
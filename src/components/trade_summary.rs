@@ -1,4 +1,5 @@
-use crate::Trade;
+use crate::currency::DisplayCurrency;
+use magic_eden_sft_core::{Trade, TradeWindow, WindowStats};
 use rust_decimal::Decimal;
 use yew::prelude::*;
 
@@ -9,42 +10,70 @@ pub struct TradeSummaryProps {
 
 #[function_component(TradeSummary)]
 pub fn trade_summary(props: &TradeSummaryProps) -> Html {
+    let window = use_state(TradeWindow::default);
+    let currency = use_context::<DisplayCurrency>().unwrap_or_default();
+
     let trades = match &props.trades {
         Some(trades) => trades,
         None => return html!(<></>),
     };
 
-    let count = trades.len();
+    let stats = WindowStats::new(trades, *window, crate::now_ts());
+
+    let toggle = TradeWindow::ALL.into_iter().map(|option| {
+        let window = window.clone();
+        let class = match *window == option {
+            true => "btn btn-secondary",
+            false => "btn btn-outline-secondary",
+        };
+        let onclick = Callback::from(move |_| window.set(option));
 
-    let (sum_amount, sum_value) = trades.iter().fold(
-        (Decimal::ZERO, Decimal::ZERO),
-        |(mut sum_amount, mut sum_value), trade| {
-            sum_amount += trade.amount;
-            sum_value += trade.amount * trade.price;
+        html!(<button type="button" { class } { onclick }>{ option.label() }</button>)
+    });
 
-            (sum_amount, sum_value)
-        },
-    );
-    let avg_price = sum_value / sum_amount;
+    let fmt_price = |price: Option<Decimal>| match price {
+        Some(price) => currency.format(price, 3),
+        None => String::from("-"),
+    };
+    let change = match stats.change_percent {
+        Some(change) if change.is_sign_negative() => {
+            html!(<span class="text-danger">{ format!("{}%", change.round_dp(1)) }</span>)
+        }
+        Some(change) => html!(<span class="text-success">{ format!("+{}%", change.round_dp(1)) }</span>),
+        None => html!({ "-" }),
+    };
 
     let trades = trades.iter().map(|trade| {
-        let id = format!("{}{}", trade.ts, trade.price);
+        let id = format!("{}{}", trade.ts.timestamp_millis(), trade.price);
+
+        let ts = trade.ts.format("%Y-%m-%d %H:%M:%S").to_string();
+        let ts = match trade.approximate {
+            true => html!(<span title="Seen in the event queue, happened at or before">{ format!("≤ {ts}") }</span>),
+            false => html!({ ts }),
+        };
 
         html!(<tr { id }>
-            <td>{ trade.ts.trim_end_matches(".000") }</td>
-            <td>{ trade.price.round_dp(3).to_string() }</td>
+            <td>{ ts }</td>
+            <td>{ currency.amount(trade.price, 3) }</td>
             <td>{ trade.amount.to_string() }</td>
         </tr>)
     });
 
     html!(<div style="height: 250px; overflow: auto">
-        <b>{ "Trades: " }</b>{ count }<br/>
-        <b>{ "Avg. price: "}</b>{ avg_price.round_dp(3).to_string() }<br/>
+        <div class="btn-group btn-group-sm my-1">
+            { for toggle }
+        </div><br/>
+        <b>{ "Trades: " }</b>{ stats.count }<br/>
+        <b>{ "Volume: " }</b>{ stats.volume.to_string() }<br/>
+        <b>{ "VWAP: " }</b>{ fmt_price(stats.vwap) }<br/>
+        <b>{ "Min / median / max: " }</b>
+        { format!("{} / {} / {}", fmt_price(stats.min), fmt_price(stats.median), fmt_price(stats.max)) }<br/>
+        <b>{ format!("Change vs previous {}: ", window.label()) }</b>{ change }<br/>
         <table class="table table-striped table-bordered">
             <thead>
                 <tr>
-                    <th>{ "Date" }</th>
-                    <th>{ "Price" }</th>
+                    <th>{ "Date (UTC)" }</th>
+                    <th>{ format!("Price ({})", currency.symbol()) }</th>
                     <th>{ "Amount" }</th>
                 </tr>
             </thead>
@@ -0,0 +1,55 @@
+use gloo_storage::{LocalStorage, Storage};
+use magic_eden_sft_core::prices::{Currency, ExchangeRates};
+use rust_decimal::Decimal;
+
+const STORAGE_KEY: &str = "currency";
+
+/// Currency prices are displayed in, provided to every component as a context
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DisplayCurrency {
+    pub selected: Currency,
+    pub rates: Option<ExchangeRates>,
+}
+
+impl DisplayCurrency {
+    /// The selected currency, or SOL until there are rates to convert to it
+    pub fn currency(&self) -> Currency {
+        let convertible = self
+            .rates
+            .map_or(false, |rates| rates.convert(Decimal::ONE, self.selected).is_some());
+
+        match convertible {
+            true => self.selected,
+            false => Currency::Sol,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        self.currency().symbol()
+    }
+
+    /// Amount of SOL in the display currency, without the symbol
+    pub fn amount(&self, sol: Decimal, dp: u32) -> String {
+        let amount = self
+            .rates
+            .and_then(|rates| rates.convert(sol, self.currency()))
+            .unwrap_or(sol);
+
+        amount.round_dp(dp).to_string()
+    }
+
+    /// Amount of SOL in the display currency, with the symbol
+    pub fn format(&self, sol: Decimal, dp: u32) -> String {
+        format!("{} {}", self.amount(sol, dp), self.symbol())
+    }
+}
+
+pub fn load_currency() -> Currency {
+    LocalStorage::get(STORAGE_KEY).unwrap_or_default()
+}
+
+pub fn save_currency(currency: Currency) {
+    if let Err(e) = LocalStorage::set(STORAGE_KEY, currency) {
+        crate::console_log!("Failed to save the currency: {}", e);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::error::send_json;
+use crate::rpc::RpcClient;
+use futures::future::{FutureExt, LocalBoxFuture};
+use gloo_net::http::Request;
+use magic_eden_sft_core::prices::{jupiter_price_url, JupiterPrice, SOL_MINT, USDC_MINT};
+use magic_eden_sft_core::rpc::{EncodedUiAccount, UiAccount};
+use magic_eden_sft_core::{FetchError, SftTrades, FLIPSIDE_TRADES_URL};
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use web_sys::UrlSearchParams;
+
+/// Where the fixtures are served from, relative to the app
+const FIXTURES_PATH: &str = "fixtures";
+
+/// Everything the app loads from the outside world. The live source talks to
+/// the RPC nodes, Jupiter and Flipside, while the fixture source replays
+/// responses recorded with `sft-tracker record-fixtures`.
+pub trait MarketDataSource {
+    fn get_multiple_accounts<'a>(
+        &'a self,
+        addresses: &'a [String],
+    ) -> LocalBoxFuture<'a, Result<Vec<Option<UiAccount>>, FetchError>>;
+
+    /// Price of the token in SOL
+    fn token_price<'a>(&'a self, token: &'a str) -> LocalBoxFuture<'a, Result<Decimal, FetchError>>;
+
+    /// Price of the token in USD
+    fn usd_price<'a>(&'a self, token: &'a str) -> LocalBoxFuture<'a, Result<Decimal, FetchError>>;
+
+    /// Last 30 days of trades per market
+    fn trades(&self) -> LocalBoxFuture<'_, Result<Vec<SftTrades>, FetchError>>;
+
+    /// Token balances of the owner, by mint
+    fn token_balances<'a>(&'a self, owner: &'a str) -> LocalBoxFuture<'a, Result<HashMap<String, Decimal>, FetchError>>;
+
+    /// Client to subscribe to orderbook updates with, for sources that have
+    /// live updates
+    fn rpc(&self) -> Option<RpcClient>;
+}
+
+/// Source selected through the `source` query parameter, `fixtures` to replay
+/// the recorded responses and live otherwise
+pub fn from_query(rpc: RpcClient) -> Rc<dyn MarketDataSource> {
+    let source = web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .and_then(|search| UrlSearchParams::new_with_str(&search).ok())
+        .and_then(|params| params.get("source"));
+
+    match source.as_deref() {
+        Some("fixtures") => Rc::new(FixtureSource::new(FIXTURES_PATH)),
+        _ => Rc::new(LiveSource::new(rpc)),
+    }
+}
+
+pub struct LiveSource {
+    rpc: RpcClient,
+}
+
+impl LiveSource {
+    pub fn new(rpc: RpcClient) -> Self {
+        Self { rpc }
+    }
+}
+
+impl MarketDataSource for LiveSource {
+    fn get_multiple_accounts<'a>(
+        &'a self,
+        addresses: &'a [String],
+    ) -> LocalBoxFuture<'a, Result<Vec<Option<UiAccount>>, FetchError>> {
+        self.rpc.get_multiple_accounts(addresses).boxed_local()
+    }
+
+    fn token_price<'a>(&'a self, token: &'a str) -> LocalBoxFuture<'a, Result<Decimal, FetchError>> {
+        jupiter_price(token, SOL_MINT).boxed_local()
+    }
+
+    fn usd_price<'a>(&'a self, token: &'a str) -> LocalBoxFuture<'a, Result<Decimal, FetchError>> {
+        jupiter_price(token, USDC_MINT).boxed_local()
+    }
+
+    fn trades(&self) -> LocalBoxFuture<'_, Result<Vec<SftTrades>, FetchError>> {
+        send_json(Request::get(FLIPSIDE_TRADES_URL)).boxed_local()
+    }
+
+    fn token_balances<'a>(&'a self, owner: &'a str) -> LocalBoxFuture<'a, Result<HashMap<String, Decimal>, FetchError>> {
+        self.rpc.get_token_balances(owner).boxed_local()
+    }
+
+    fn rpc(&self) -> Option<RpcClient> {
+        Some(self.rpc.clone())
+    }
+}
+
+async fn jupiter_price(token: &str, vs_token: &str) -> Result<Decimal, FetchError> {
+    let res: JupiterPrice = send_json(Request::get(&jupiter_price_url(token, vs_token))).await?;
+
+    res.data
+        .get(token)
+        .map(|data| data.price)
+        .ok_or_else(|| FetchError::Missing(format!("{token} price")))
+}
+
+/// Replays the responses stored under the fixtures path:
+/// - `accounts.json`: account address to the account as returned by
+///   `getMultipleAccounts` (or `null`)
+/// - `prices.json`: token mint to its price in SOL
+/// - `usd_prices.json`: token mint to its price in USD (or `null`)
+/// - `trades.json`: the Flipside trades response
+/// - `balances.json`: owner to their token balances by mint
+///
+/// Accounts missing from the recording are reported as not found, just like
+/// the RPC node would.
+pub struct FixtureSource {
+    path: String,
+    accounts: RefCell<Option<Rc<HashMap<String, Value>>>>,
+}
+
+impl FixtureSource {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            accounts: RefCell::new(None),
+        }
+    }
+
+    async fn load<T: DeserializeOwned>(&self, file: &str) -> Result<T, FetchError> {
+        send_json(Request::get(&format!("{}/{}", self.path, file))).await
+    }
+
+    /// The accounts recording is large, so it's only loaded once
+    async fn accounts(&self) -> Result<Rc<HashMap<String, Value>>, FetchError> {
+        if let Some(accounts) = self.accounts.borrow().as_ref() {
+            return Ok(accounts.clone());
+        }
+
+        let accounts = Rc::new(self.load("accounts.json").await?);
+        *self.accounts.borrow_mut() = Some(Rc::clone(&accounts));
+
+        Ok(accounts)
+    }
+}
+
+impl MarketDataSource for FixtureSource {
+    fn get_multiple_accounts<'a>(
+        &'a self,
+        addresses: &'a [String],
+    ) -> LocalBoxFuture<'a, Result<Vec<Option<UiAccount>>, FetchError>> {
+        async move {
+            let accounts = self.accounts().await?;
+
+            addresses
+                .iter()
+                .map(|address| {
+                    let account = match accounts.get(address) {
+                        Some(account) => account.clone(),
+                        None => return Ok(None),
+                    };
+
+                    let account: Option<EncodedUiAccount> =
+                        serde_json::from_value(account).map_err(|e| FetchError::Json(e.to_string()))?;
+
+                    account.map(UiAccount::try_from).transpose()
+                })
+                .collect()
+        }
+        .boxed_local()
+    }
+
+    fn token_price<'a>(&'a self, token: &'a str) -> LocalBoxFuture<'a, Result<Decimal, FetchError>> {
+        async move {
+            let prices: HashMap<String, Decimal> = self.load("prices.json").await?;
+
+            prices
+                .get(token)
+                .copied()
+                .ok_or_else(|| FetchError::Missing(format!("{token} price")))
+        }
+        .boxed_local()
+    }
+
+    fn usd_price<'a>(&'a self, token: &'a str) -> LocalBoxFuture<'a, Result<Decimal, FetchError>> {
+        async move {
+            let prices: HashMap<String, Option<Decimal>> = self.load("usd_prices.json").await?;
+
+            prices
+                .get(token)
+                .copied()
+                .flatten()
+                .ok_or_else(|| FetchError::Missing(format!("{token} USD price")))
+        }
+        .boxed_local()
+    }
+
+    fn trades(&self) -> LocalBoxFuture<'_, Result<Vec<SftTrades>, FetchError>> {
+        self.load("trades.json").boxed_local()
+    }
+
+    fn token_balances<'a>(&'a self, owner: &'a str) -> LocalBoxFuture<'a, Result<HashMap<String, Decimal>, FetchError>> {
+        async move {
+            let mut balances: HashMap<String, HashMap<String, Decimal>> = self.load("balances.json").await?;
+
+            Ok(balances.remove(owner).unwrap_or_default())
+        }
+        .boxed_local()
+    }
+
+    fn rpc(&self) -> Option<RpcClient> {
+        None
+    }
+}
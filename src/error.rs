@@ -0,0 +1,31 @@
+use gloo_net::http::{Request, Response};
+use magic_eden_sft_core::FetchError;
+use serde::de::DeserializeOwned;
+
+/// Sends the request and parses the JSON body, treating non-2xx statuses as
+/// errors
+pub async fn send_json<T: DeserializeOwned>(request: Request) -> Result<T, FetchError> {
+    let res = request
+        .send()
+        .await
+        .map_err(|e| FetchError::Http(e.to_string()))?;
+
+    parse_json(res).await
+}
+
+pub async fn parse_json<T: DeserializeOwned>(res: Response) -> Result<T, FetchError> {
+    if res.status() == 429 {
+        let retry_after = res
+            .headers()
+            .get("Retry-After")
+            .and_then(|retry_after| retry_after.trim().parse().ok());
+
+        return Err(FetchError::RateLimited { retry_after });
+    }
+
+    if !res.ok() {
+        return Err(FetchError::Http(format!("{} {}", res.status(), res.status_text())));
+    }
+
+    res.json().await.map_err(|e| FetchError::Json(e.to_string()))
+}
@@ -1,19 +1,40 @@
 mod components;
-mod utils;
-
+mod currency;
+mod data_source;
+mod error;
+mod rpc;
+mod subscriptions;
+
+use self::components::arbitrage_report::ArbitrageReport;
+use self::components::collection_dashboard::{CollectionDashboard, COLLECTIONS};
+use self::components::craft_planner::CraftPlanner;
 use self::components::open_orders::OpenOrders;
 use self::components::pagination::{Pagination, PaginationProps};
+use self::components::price_chart::PriceChart;
+use self::components::recipe_tree::RecipeTree;
+use self::components::rpc_settings::RpcSettings;
+use self::components::status_bar::{DataSource, SourceStatus, StatusBar};
+use self::components::sweep_calculator::SweepCalculator;
 use self::components::trade_summary::TradeSummary;
-use self::utils::{Listing, Listings};
+use self::currency::DisplayCurrency;
+use self::data_source::MarketDataSource;
+use self::rpc::{RpcClient, RpcEndpoint};
+use self::subscriptions::subscribe_markets;
+use chrono::{DateTime, TimeZone, Utc};
 use dex_v4::state::{UserAccountHeader, UserAccount};
-use gloo_net::http::Request;
+use futures::stream::{FuturesUnordered, StreamExt};
+use gloo_storage::{LocalStorage, Storage};
+use gloo_timers::callback::{Interval, Timeout};
+use magic_eden_sft_core::prices::{Currency, ExchangeRates, GENE_MINT, KI_MINT, SOL_MINT};
+use magic_eden_sft_core::{
+    collection_overviews, craft_opportunities, decode_dex_state, decode_fills, Costing, CraftPlan, decode_orderbook_state, genopets_markets,
+    FeeModel, FetchError, Fill, FillTrades, Listings, MagicEdenItem, MarketAccounts, MarketMetrics, MarketOrders, MarketPrices, MarketSort, OrderChanges,
+    Recipes, Side, SideUpdate, SortColumn, Trade,
+};
 use rust_decimal::Decimal;
-use serde::{de, Deserialize};
-use serde_json::json;
-use solana_sdk::{account::Account, pubkey, pubkey::Pubkey};
-use std::{collections::HashMap, str::FromStr};
-use utils::Recipes;
-use web_sys::HtmlInputElement;
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashMap, future::Future, rc::Rc, str::FromStr};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
 
 #[allow(unused)]
@@ -26,8 +47,16 @@ macro_rules! console_log {
 #[allow(unused)]
 pub(crate) use console_log;
 
-const SERUM_V4: Pubkey = pubkey!("srmv4uTCPF81hWDaPyEN2mLZ8XbvzuEM6LsAxR8NpjU");
 const PAGE_SIZE: usize = 25;
+/// How long order changes stay highlighted after a refresh
+const HIGHLIGHT_MS: u32 = 5_000;
+/// Each market needs its asks, bids and event queue, which have to fit in a
+/// single 100 accounts request
+const MARKETS_PER_CHUNK: usize = 33;
+const SORT_STORAGE_KEY: &str = "sort";
+
+/// Event queue fills by base vault, with the fees of their market
+type Fills = HashMap<String, (FeeModel, Vec<Fill>)>;
 
 fn main() {
     yew::Renderer::<App>::new().render();
@@ -47,25 +76,82 @@ pub struct SearchFormData {
     collection: String,
 }
 
+fn load_sort() -> MarketSort {
+    LocalStorage::get(SORT_STORAGE_KEY).unwrap_or_default()
+}
+
+fn save_sort(sort: MarketSort) {
+    if let Err(e) = LocalStorage::set(SORT_STORAGE_KEY, sort) {
+        console_log!("Failed to save the sort: {}", e);
+    }
+}
+
 pub struct App {
-    orders: HashMap<String, Vec<Listing>>,
+    market_accounts: HashMap<String, MarketAccounts>,
+    orders: HashMap<String, MarketOrders>,
+    /// Trade history by base vault
     trades: HashMap<String, Vec<Trade>>,
-    token_prices: Option<(Decimal, Decimal)>,
+    /// Trades from the event queue fills, by base vault
+    fills: HashMap<String, FillTrades>,
+    /// Fills followed by the trade history by token address, only for markets
+    /// with either. See `App::merge_trades`
+    item_trades: HashMap<String, Vec<Trade>>,
+    token_prices: Option<ExchangeRates>,
+    /// Currency prices are displayed in
+    currency: Currency,
     recipes: Recipes,
     markets: Vec<MagicEdenItem>,
     search_data: SearchFormData,
     search_form: SearchForm,
+    /// Saved locally, see `AppMsg::Sort`
+    sort: MarketSort,
     page: usize,
     user_accounts: HashMap<Pubkey, UserAccountHeader>,
+    /// Token balances of the owner, by mint
+    holdings: HashMap<String, Decimal>,
+    /// Items to plan crafting for, with their quantities
+    plan_targets: Vec<(String, u64)>,
+    rpc: RpcClient,
+    source: Rc<dyn MarketDataSource>,
+    rpc_endpoints: Vec<RpcEndpoint>,
+    statuses: HashMap<DataSource, SourceStatus>,
+    /// Whether a markets load that ends in a websocket subscription is running
+    subscribed: bool,
+    refresh: Option<Interval>,
+    refresh_secs: u32,
+    /// Recent order changes per market, with the time they were detected
+    order_changes: HashMap<String, (f64, OrderChanges)>,
 }
 
 pub enum AppMsg {
-    Orders(HashMap<String, Vec<Listing>>),
+    MarketAccounts(HashMap<String, MarketAccounts>),
+    /// Loaded or updated sides, any side not included is kept as it is
+    Orders(Vec<SideUpdate>),
+    Progress(DataSource, (usize, usize)),
     Trades(HashMap<String, Vec<Trade>>),
-    TokenPrices(Decimal, Decimal),
+    Fills(Fills),
+    TokenPrices(ExchangeRates),
+    Currency(Currency),
     Search(SearchFormData),
     Page(usize),
+    FilterCollection(String),
+    Sort(MarketSort),
     UserAccounts(HashMap<Pubkey, UserAccountHeader>),
+    Holdings(HashMap<String, Decimal>),
+    PlanTargets(Vec<(String, u64)>),
+    RpcHealth(Vec<RpcEndpoint>),
+    RpcEndpoints(Vec<String>),
+    FetchFailed(DataSource, FetchError),
+    /// A markets load failed, with whether it was the one that would have
+    /// kept running as the subscription
+    MarketsFailed(FetchError, bool),
+    /// Some orderbooks of a markets load couldn't be decoded. The load still
+    /// goes on with the others
+    DecodeFailed(FetchError),
+    Retry(DataSource),
+    Refresh,
+    RefreshInterval(u32),
+    ExpireChanges,
 }
 
 impl From<&SearchForm> for AppMsg {
@@ -95,37 +181,117 @@ impl Component for App {
     type Properties = ();
 
     fn create(ctx: &Context<Self>) -> Self {
-        let cb_orders = ctx.link().callback(|orders| AppMsg::Orders(orders));
-        let cb_trades = ctx.link().callback(|trades| AppMsg::Trades(trades));
-        let cb_token_prices = ctx
-            .link()
-            .callback(|(ki_price, gene_price)| AppMsg::TokenPrices(ki_price, gene_price));
-        let genopets_sfts = include_str!("../collections/genopets_sfts.json");
-
-        let markets: Vec<MagicEdenItem> = serde_json::from_str(genopets_sfts).unwrap();
+        let rpc = RpcClient::new(rpc::load_endpoints(), ctx.link().callback(AppMsg::RpcHealth));
+        let source = data_source::from_query(rpc.clone());
+        let markets = genopets_markets();
         let recipes = Recipes::new(markets.clone());
 
-        wasm_bindgen_futures::spawn_local(sync_markets(markets.clone(), cb_orders));
-        wasm_bindgen_futures::spawn_local(fetch_trades(cb_trades));
-        wasm_bindgen_futures::spawn_local(token_prices(cb_token_prices));
-
-        Self {
+        let mut app = Self {
+            market_accounts: HashMap::new(),
             orders: HashMap::new(),
             trades: HashMap::new(),
+            fills: HashMap::new(),
+            item_trades: HashMap::new(),
             token_prices: None,
+            currency: currency::load_currency(),
             recipes,
             markets,
             search_data: SearchFormData::default(),
             search_form: SearchForm::default(),
+            sort: load_sort(),
             page: 0,
             user_accounts: HashMap::new(),
-        }
+            holdings: HashMap::new(),
+            plan_targets: vec![],
+            rpc_endpoints: rpc.endpoints(),
+            rpc,
+            source,
+            statuses: HashMap::new(),
+            subscribed: false,
+            refresh: None,
+            refresh_secs: 0,
+            order_changes: HashMap::new(),
+        };
+
+        app.fetch(ctx, DataSource::Markets);
+        app.fetch(ctx, DataSource::Trades);
+        app.fetch(ctx, DataSource::TokenPrices);
+
+        app
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            AppMsg::Orders(orders) => self.orders = orders,
-            AppMsg::Trades(trades) => self.trades = trades,
+            AppMsg::MarketAccounts(market_accounts) => {
+                self.market_accounts.extend(market_accounts);
+                self.merge_trades();
+            }
+            // Subscriptions send single market updates, so orders are merged
+            AppMsg::Orders(updates) => {
+                let mut orders = HashMap::<String, MarketOrders>::new();
+                for update in updates {
+                    let market = orders
+                        .entry(update.token_address.clone())
+                        .or_insert_with(|| self.orders.get(&update.token_address).cloned().unwrap_or_default());
+
+                    *market.side_mut(update.side) = update.listings;
+                }
+
+                let now = js_sys::Date::now();
+                let mut detected = false;
+
+                for (token_address, new_orders) in &orders {
+                    let changes = match self.orders.get(token_address) {
+                        Some(old_orders) => new_orders.diff(old_orders),
+                        None => continue,
+                    };
+
+                    if !changes.is_empty() {
+                        self.order_changes.insert(token_address.clone(), (now, changes));
+                        detected = true;
+                    }
+                }
+
+                if detected {
+                    let link = ctx.link().clone();
+                    Timeout::new(HIGHLIGHT_MS, move || link.send_message(AppMsg::ExpireChanges)).forget();
+                }
+
+                self.orders.extend(orders);
+            }
+            AppMsg::Progress(source, (loaded, total)) => {
+                let status = match loaded < total {
+                    true => SourceStatus::Loading(Some((loaded, total))),
+                    false => SourceStatus::Ok,
+                };
+
+                self.statuses.insert(source, status);
+            }
+            AppMsg::Trades(trades) => {
+                self.trades = trades;
+
+                for (base_vault, fills) in self.fills.iter_mut() {
+                    fills.dedup(self.trades.get(base_vault).map_or(&[][..], Vec::as_slice));
+                }
+
+                self.merge_trades();
+                self.statuses.insert(DataSource::Trades, SourceStatus::Ok);
+            }
+            // Fills stay in the event queue until cranked, so the same ones
+            // show up in several loads
+            AppMsg::Fills(fills) => {
+                let now = now_ts();
+
+                for (base_vault, (fees, new_fills)) in fills {
+                    let history = self.trades.get(&base_vault).map_or(&[][..], Vec::as_slice);
+                    let known = self.fills.entry(base_vault).or_default();
+
+                    known.load(&new_fills, &fees, now);
+                    known.dedup(history);
+                }
+
+                self.merge_trades();
+            }
             AppMsg::Search(data) => {
                 let old_data = std::mem::replace(&mut self.search_data, data);
 
@@ -138,29 +304,98 @@ impl Component for App {
                     _ => None,
                 };
 
-                if let Some(new_owner) = new_owner {
-                    let new_owner = new_owner.to_bytes();
-                    let cb_accounts = ctx.link().callback(AppMsg::UserAccounts);
-
-                    let accounts = self.markets
-                        .iter()
-                        .map(|item| {
-                            let seeds: [&[u8]; 2] = [&item.market_address.to_bytes(), &new_owner];
-
-                            Pubkey::find_program_address(&seeds, &SERUM_V4).0.to_string()
-                        })
-                        .collect();
+                if new_owner.is_some() {
+                    self.fetch(ctx, DataSource::UserAccounts);
+                    self.fetch(ctx, DataSource::Holdings);
+                }
 
-                    wasm_bindgen_futures::spawn_local(sync_accounts(accounts, cb_accounts));
+                // The planner would keep subtracting the previous owner's
+                // holdings
+                if !self.has_owner() {
+                    self.holdings.clear();
+                    self.statuses.remove(&DataSource::Holdings);
                 }
             },
             AppMsg::Page(page) => self.page = page,
-            AppMsg::TokenPrices(ki_price, gene_price) => {
-                self.token_prices = Some((ki_price, gene_price));
+            AppMsg::FilterCollection(collection) => {
+                if let Some(select) = self.search_form.collection.cast::<HtmlSelectElement>() {
+                    select.set_value(&collection);
+                }
+
+                self.search_data.collection = collection;
+                self.page = 0;
+            }
+            AppMsg::Sort(sort) => {
+                self.sort = sort;
+                self.page = 0;
+                save_sort(sort);
+            }
+            AppMsg::TokenPrices(rates) => {
+                self.token_prices = Some(rates);
+                self.statuses.insert(DataSource::TokenPrices, SourceStatus::Ok);
             }
             AppMsg::UserAccounts(accounts) => {
                 self.user_accounts = accounts;
+                self.statuses.insert(DataSource::UserAccounts, SourceStatus::Ok);
+            }
+            // Holdings of an owner that was cleared while they loaded are
+            // dropped
+            AppMsg::Holdings(holdings) => {
+                if self.has_owner() {
+                    self.holdings = holdings;
+                    self.statuses.insert(DataSource::Holdings, SourceStatus::Ok);
+                }
+            }
+            AppMsg::PlanTargets(targets) => self.plan_targets = targets,
+            AppMsg::Currency(currency) => {
+                currency::save_currency(currency);
+                self.currency = currency;
+            }
+            AppMsg::RpcHealth(endpoints) => self.rpc_endpoints = endpoints,
+            AppMsg::RpcEndpoints(urls) => {
+                let urls = rpc::or_default_endpoints(urls);
+
+                rpc::save_endpoints(&urls);
+                self.rpc.set_endpoints(urls);
+                self.rpc_endpoints = self.rpc.endpoints();
+            }
+            AppMsg::Refresh => {
+                self.fetch(ctx, DataSource::Markets);
+                self.fetch(ctx, DataSource::Trades);
+                self.fetch(ctx, DataSource::TokenPrices);
+            }
+            AppMsg::RefreshInterval(secs) => {
+                self.refresh_secs = secs;
+                self.refresh = (secs > 0).then(|| {
+                    let link = ctx.link().clone();
+
+                    Interval::new(secs * 1_000, move || link.send_message(AppMsg::Refresh))
+                });
             }
+            AppMsg::ExpireChanges => {
+                let now = js_sys::Date::now();
+
+                self.order_changes
+                    .retain(|_, (detected, _)| now - *detected < HIGHLIGHT_MS as f64);
+            }
+            AppMsg::MarketsFailed(e, subscribing) => {
+                console_log!("{:?} failed: {}", DataSource::Markets, e);
+                self.statuses.insert(DataSource::Markets, SourceStatus::Failed(e));
+
+                // Refreshes failing leave the subscription running
+                if subscribing {
+                    self.subscribed = false;
+                }
+            }
+            AppMsg::FetchFailed(source, e) => {
+                console_log!("{:?} failed: {}", source, e);
+                self.statuses.insert(source, SourceStatus::Failed(e));
+            }
+            AppMsg::DecodeFailed(e) => {
+                console_log!("{:?} failed: {}", DataSource::Markets, e);
+                self.statuses.insert(DataSource::Markets, SourceStatus::Failed(e));
+            }
+            AppMsg::Retry(source) => self.fetch(ctx, source),
         }
 
         true
@@ -181,11 +416,7 @@ impl Component for App {
                 return None;
             }
 
-            let owner_key = self.search_data.owner.map(|owner_key| {
-                let seeds: &[&[u8]] = &[&item.market_address.to_bytes(), &owner_key.to_bytes()];
-
-                Pubkey::find_program_address(seeds, &SERUM_V4).0
-            });
+            let owner_key = self.search_data.owner.map(|owner_key| item.user_account_address(&owner_key));
 
             if let Some(owner_key) = &owner_key {
                 if !self.user_accounts.contains_key(owner_key) {
@@ -196,55 +427,115 @@ impl Component for App {
             Some((item, orders, owner_key))
         });
 
+        let display_currency = DisplayCurrency {
+            selected: self.currency,
+            rates: self.token_prices,
+        };
+
+        let prices = self.token_prices.map(|rates| {
+            MarketPrices::new(rates.tokens(), &self.orders, |token_address| {
+                self.market_accounts
+                    .get(token_address)
+                    .map(|accounts| accounts.fees)
+                    .unwrap_or_default()
+            })
+        });
+        let mut costing = prices.as_ref().map(|prices| Costing::new(&self.recipes, prices));
+
+        let now = now_ts();
+        let mut markets = markets
+            .map(|(item, orders, owner_key)| {
+                let fees = self.market_accounts
+                    .get(&item.token_address)
+                    .map(|accounts| accounts.fees)
+                    .unwrap_or_default();
+                let trades = self.item_trades.get(&item.token_address);
+                let production_cost = costing.as_mut().and_then(|costing| costing.craft_cost(&item.token_address));
+                let metrics = MarketMetrics::new(item, orders, &fees, trades.map(Vec::as_slice), production_cost, now);
+
+                ((item, orders, owner_key, trades), metrics)
+            })
+            .collect::<Vec<_>>();
+        self.sort.sort(&mut markets);
+
         let pagination_props = PaginationProps {
             current: self.page,
-            count: markets.clone().count(),
+            count: markets.len(),
             page_size: 25,
             onclick: ctx.link().callback(|page| AppMsg::Page(page)),
         };
 
         let markets = markets
+            .into_iter()
             .skip(self.page * PAGE_SIZE)
             .take(PAGE_SIZE)
-            .map(|(item, orders, owner_key)| {
-                let trades = self.trades.get(&item.base_vault_address).cloned();
+            .map(|((item, orders, owner_key, trades), metrics)| {
+                let accounts = self.market_accounts.get(&item.token_address);
+                let fees = accounts.map(|accounts| accounts.fees).unwrap_or_default();
+                let changes = self.order_changes
+                    .get(&item.token_address)
+                    .map(|(_, changes)| changes.clone())
+                    .unwrap_or_default();
+                // Trades are at the maker's price, so the floor is too
+                let floor = orders.best_ask().map(|listing| fees.raw_price(listing.price));
+
+                let mismatches = accounts
+                    .map(|accounts| accounts.mismatches(item))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|mismatch| html!(<div class="text-danger small">
+                        { format!("{} differs from chain: {}", mismatch.field, mismatch.actual) }
+                    </div>));
 
                 let recipe = match self.recipes.get(&item.token_address) {
-                    Some(recipe) => {
-                        let reagents = recipe.reagents
-                            .iter()
-                            .map(|(reagent, amount)| html!(<li>{ format!("{} {}", amount, reagent) }</li>));
-
-                        let total_cost = self.token_prices
-                            .map(|(ki_price, gene_price)| {
-                                let ki_cost = recipe.ki_cost * ki_price;
-                                let gene_cost = recipe.gene_cost * gene_price;
-                                let total_cost = ki_cost + gene_cost;
-
-                                format!(" {} SOL", total_cost.round_dp(4))
-                            })
+                    Ok(Some(recipe)) => {
+                        let tree = costing.as_mut().and_then(|costing| costing.tree(&item.token_address));
+                        let reagents = match tree {
+                            Some(root) => html!(<RecipeTree { root } />),
+                            None => {
+                                let reagents = recipe.reagents
+                                    .iter()
+                                    .map(|(reagent, amount)| html!(<li>{ format!("{} {}", amount, reagent) }</li>));
+
+                                html!(<ul>{ for reagents }</ul>)
+                            }
+                        };
+
+                        // Same craft-or-buy cost as the tree below, and the
+                        // one markets are sorted by
+                        let total_cost = metrics
+                            .production_cost
+                            .map(|total_cost| format!(" {}", display_currency.format(total_cost, 4)))
                             .unwrap_or_default();
 
                         html!(<>
                             { total_cost }
                             <hr/>
                             <h6>{ "Recipe" }</h6>
-                            <ul>
-                                { for reagents }
-                            </ul>
+                            { reagents }
                         </>)
                     }
-                    None => html!(<></>),
+                    Ok(None) => html!(<></>),
+                    Err(e) => html!(<div class="text-danger small">{ e.to_string() }</div>),
                 };
 
                 html!(<tr key={ item.token_address.clone() }>
                     <td>
                         <img src={ Some(item.token_image.clone()) } style="width: 230px; height: 230px" /><br/>
                         <a href={ format!("https://magiceden.io/sft/{}", item.market_address) } target="_blank">{ &item.token_title }</a>
+                        { for mismatches }
                     </td>
                     <td>{ recipe }</td>
-                    <td><OpenOrders orders={ orders.clone() } {owner_key} /></td>
-                    <td><TradeSummary { trades } /></td>
+                    <td>
+                        <OpenOrders orders={ orders.clone() } { fees } {owner_key} { changes } />
+                        <SweepCalculator orders={ orders.clone() } { fees } />
+                    </td>
+                    <td>
+                        if let Some(trades) = trades.cloned() {
+                            <PriceChart { trades } { floor } />
+                        }
+                        <TradeSummary trades={ trades.cloned() } />
+                    </td>
                 </tr>)
             });
 
@@ -257,13 +548,13 @@ impl Component for App {
                     }
 
                     let market = account.market.to_string();
-                    let pending = Decimal::from_i128_with_scale(account.quote_token_free as i128, 9).round_dp(3);
+                    let pending = Decimal::from_i128_with_scale(account.quote_token_free as i128, 9);
 
                     Some(html!(<tr key={ key.to_string() }>
                         <td>
                             <a href={ format!("https://magiceden.io/sft/{market}") } target="_blank">{ market }</a>
                         </td>
-                        <td>{ pending }</td>
+                        <td>{ display_currency.amount(pending, 3) }</td>
                     </tr>))
                 });
 
@@ -271,7 +562,7 @@ impl Component for App {
                 <thead>
                     <tr>
                         <th>{ "Market" }</th>
-                        <th>{ "Pending SOL collect" }</th>
+                        <th>{ format!("Pending collect ({})", display_currency.symbol()) }</th>
                     </tr>
                 </thead>
                 <tbody>
@@ -280,50 +571,173 @@ impl Component for App {
             </table>)
         });
 
+        let arbitrage = prices.as_ref().map(|prices| {
+            let opportunities = craft_opportunities(&self.recipes, &self.markets, prices);
+
+            html!(<ArbitrageReport { opportunities } />)
+        });
+
+        let planner = {
+            let targets = self.plan_targets
+                .iter()
+                .map(|(token_address, quantity)| (token_address.clone(), Decimal::from(*quantity)))
+                .collect::<Vec<_>>();
+            let plan = CraftPlan::new(&self.recipes, &targets, &self.holdings);
+            let shopping = self.token_prices.map(|rates| {
+                plan.shopping_cost(&self.orders, |token_address| {
+                    self.market_accounts
+                        .get(token_address)
+                        .map(|accounts| accounts.fees)
+                        .unwrap_or_default()
+                }, rates.tokens())
+            });
+            let craftable = self.markets
+                .iter()
+                .filter(|item| self.recipes.recipe(&item.token_address).is_some())
+                .map(|item| (item.token_address.clone(), item.token_title.clone()))
+                .collect::<Vec<_>>();
+            let titles = self.markets
+                .iter()
+                .map(|item| (item.token_address.clone(), item.token_title.clone()))
+                .collect::<HashMap<_, _>>();
+            let ontargets = ctx.link().callback(AppMsg::PlanTargets);
+
+            html!(<CraftPlanner
+                { craftable }
+                { titles }
+                targets={ self.plan_targets.clone() }
+                { plan }
+                { shopping }
+                has_owner={ self.has_owner() }
+                { ontargets }
+            />)
+        };
+
+        let dashboard = {
+            let overviews = collection_overviews(&self.markets, &self.orders, &self.item_trades, |token_address| {
+                self.market_accounts
+                    .get(token_address)
+                    .map(|accounts| accounts.fees)
+                    .unwrap_or_default()
+            }, now);
+            let onselect = ctx.link().callback(AppMsg::FilterCollection);
+
+            html!(<CollectionDashboard { overviews } { onselect } />)
+        };
+
+        let collection_options = COLLECTIONS.into_iter().map(|(value, label)| {
+            html!(<option { value } selected={ self.search_data.collection == value }>{ label }</option>)
+        });
+
         let search_form = self.search_form.clone();
         let oninput = ctx.link().callback(move |_| AppMsg::from(&search_form));
 
         let search_form = self.search_form.clone();
         let onchange = ctx.link().callback(move |_| AppMsg::from(&search_form));
 
-        html!(<div class="container">
+        let onsave = ctx.link().callback(AppMsg::RpcEndpoints);
+
+        let mut statuses = self.statuses.clone().into_iter().collect::<Vec<_>>();
+        statuses.sort_by_key(|(source, _)| *source as u8);
+        let onretry = ctx.link().callback(AppMsg::Retry);
+
+        let onrefresh = ctx.link().callback(|e: Event| {
+            let secs = e.target_unchecked_into::<HtmlSelectElement>().value();
+
+            AppMsg::RefreshInterval(secs.parse().unwrap_or_default())
+        });
+        let refresh_options = [(0, "Off"), (30, "30 seconds"), (60, "1 minute"), (300, "5 minutes"), (900, "15 minutes")]
+            .into_iter()
+            .map(|(secs, label)| html!(<option value={ secs.to_string() } selected={ secs == self.refresh_secs }>{ label }</option>));
+
+        let oncurrency = ctx.link().batch_callback(|e: Event| {
+            let symbol = e.target_unchecked_into::<HtmlSelectElement>().value();
+
+            Currency::from_symbol(&symbol).map(AppMsg::Currency)
+        });
+        let currency_options = Currency::ALL.into_iter().map(|currency| {
+            html!(<option value={ currency.symbol() } selected={ currency == self.currency }>{ currency.symbol() }</option>)
+        });
+
+        let sort = self.sort;
+        let onsort = ctx.link().batch_callback(move |e: Event| {
+            let key = e.target_unchecked_into::<HtmlSelectElement>().value();
+
+            SortColumn::from_key(&key).map(|column| AppMsg::Sort(MarketSort { column, ..sort }))
+        });
+        let onreverse = ctx.link().callback(move |_| AppMsg::Sort(MarketSort { descending: !sort.descending, ..sort }));
+        let sort_options = SortColumn::ALL.into_iter().map(|column| {
+            html!(<option value={ column.key() } selected={ column == sort.column }>{ column.label() }</option>)
+        });
+        let direction = match sort.descending {
+            true => "Descending",
+            false => "Ascending",
+        };
+
+        html!(<ContextProvider<DisplayCurrency> context={ display_currency }><div class="container">
             <div class="row">
                 <h4>{ "Feeling generous?" }</h4>
                 <p>{ "Was this useful for you and you're feeling generous? Buy me a coffee! Feel free to send any coin to this Solana wallet:" }</p>
                 <pre>{ "7EtuuNs8jqpEUhiCQxf25mUiLzoMuCBkTnjK4YrZC5Bd" }</pre>
             </div>
             <div class="row">
-                <div class="form-group col-md-4">
+                <div class="form-group col-md-3">
                     <label class="form-label">{ "Owner" }</label>
                     <input class="form-control" ref={ self.search_form.owner.clone() } oninput={ oninput.clone() } type="text" />
                     <div class="form-text">{ "Search listings by owner address" }</div>
                 </div>
-                <div class="form-group col-md-4">
+                <div class="form-group col-md-3">
                     <label class="form-label">{ "Name" }</label>
                     <input class="form-control" ref={ self.search_form.title.clone() } { oninput } type="text" />
                 </div>
-                <div class="form-group col-md-4">
+                <div class="form-group col-md-3">
                     <label class="form-label">{ "Asset type" }</label>
                     <select class="form-select" ref={ self.search_form.collection.clone() } { onchange }>
-                        <option value="" selected=true>{ "All" }</option>
-                        <option value="genopets_augments">{ "Augment" }</option>
-                        <option value="genopets_cosmetics">{ "Cosmetic" }</option>
-                        <option value="genopets_genotype_crystals">{ "Crystal" }</option>
-                        <option value="genopets_power_ups">{ "Power up" }</option>
-                        <option value="genopets_reagents">{ "Reagent" }</option>
-                        <option value="genopets_recipe_hunt">{ "Recipe hunt missing page" }</option>
-                        <option value="genopets_terraform_seeds_sft">{ "Terraform seed" }</option>
+                        <option value="" selected={ self.search_data.collection.is_empty() }>{ "All" }</option>
+                        { for collection_options }
                     </select>
                 </div>
+                <div class="form-group col-md-3">
+                    <label class="form-label">{ "Sort by" }</label>
+                    <div class="input-group">
+                        <select class="form-select" onchange={ onsort }>
+                            { for sort_options }
+                        </select>
+                        <button type="button" class="btn btn-outline-secondary" onclick={ onreverse }>{ direction }</button>
+                    </div>
+                    <div class="form-text">{ "Items missing the figure go last" }</div>
+                </div>
             </div>
+            <RpcSettings endpoints={ self.rpc_endpoints.clone() } { onsave } />
+            <div class="row">
+                <div class="col-md-6">
+                    <StatusBar { statuses } { onretry } />
+                </div>
+                <div class="form-group col-md-3">
+                    <label class="form-label">{ "Auto-refresh" }</label>
+                    <select class="form-select" onchange={ onrefresh }>
+                        { for refresh_options }
+                    </select>
+                </div>
+                <div class="form-group col-md-3">
+                    <label class="form-label">{ "Currency" }</label>
+                    <select class="form-select" onchange={ oncurrency }>
+                        { for currency_options }
+                    </select>
+                    <div class="form-text">{ "Prices are in SOL until the exchange rates load" }</div>
+                </div>
+            </div>
+            { dashboard }
             { pending_collect }
+            { arbitrage }
+            { planner }
             <table class="table table-striped table-bordered">
                 <thead>
                     <tr>
                         <th>{ "Item" }</th>
                         <th>{ "Production cost" }</th>
                         <th>{ "Orders" }</th>
-                        <th>{ "Latest trades (30d)" }</th>
+                        <th>{ "Latest trades" }</th>
                     </tr>
                 </thead>
                 <tbody>
@@ -331,75 +745,166 @@ impl Component for App {
                 </tbody>
             </table>
             <Pagination ..pagination_props />
-        </div>)
+        </div></ContextProvider<DisplayCurrency>>)
     }
 }
 
-async fn token_prices(cb_token_prices: Callback<(Decimal, Decimal)>) {
-    let res: JupiterPrice = Request::get("https://price.jup.ag/v3/price?ids=kiGenopAScF8VF31Zbtx2Hg8qA5ArGqvnVtXb83sotc&vsToken=So11111111111111111111111111111111111111112")
-        .send()
-        .await
-        .unwrap()
-        .json()
-        .await
-        .unwrap();
-
-    let ki_price = res
-        .data
-        .get("kiGenopAScF8VF31Zbtx2Hg8qA5ArGqvnVtXb83sotc")
-        .unwrap()
-        .price;
-
-    let res: JupiterPrice = Request::get("https://price.jup.ag/v3/price?ids=GENEtH5amGSi8kHAtQoezp1XEXwZJ8vcuePYnXdKrMYz&vsToken=So11111111111111111111111111111111111111112")
-        .send()
-        .await
-        .unwrap()
-        .json()
-        .await
-        .unwrap();
-
-    let gene_price = res
-        .data
-        .get("GENEtH5amGSi8kHAtQoezp1XEXwZJ8vcuePYnXdKrMYz")
-        .unwrap()
-        .price;
-
-    cb_token_prices.emit((ki_price, gene_price));
+impl App {
+    /// Whether a valid owner address is entered
+    fn has_owner(&self) -> bool {
+        self.search_data.owner.map_or(false, |owner| owner != Pubkey::default())
+    }
+
+    /// Rebuilds `item_trades`, once the fills, the trade history or the
+    /// accounts they're matched to markets with change rather than on every
+    /// render
+    fn merge_trades(&mut self) {
+        self.item_trades = self.market_accounts
+            .iter()
+            .filter_map(|(token_address, accounts)| {
+                let base_vault = accounts.base_vault.to_string();
+
+                let fills = self.fills.get(&base_vault).into_iter().flat_map(FillTrades::trades);
+                let history = self.trades.get(&base_vault).into_iter().flatten();
+                let trades = fills.chain(history).cloned().collect::<Vec<_>>();
+
+                (!trades.is_empty()).then(|| (token_address.clone(), trades))
+            })
+            .collect();
+    }
+
+    /// Starts loading one of the data sources, reporting any failure back to
+    /// the app so it can be shown and retried
+    fn fetch(&mut self, ctx: &Context<Self>, source: DataSource) {
+        let cb_error = ctx.link().callback(move |e| AppMsg::FetchFailed(source, e));
+
+        match source {
+            DataSource::Markets => {
+                let callbacks = SyncCallbacks {
+                    market_accounts: ctx.link().callback(AppMsg::MarketAccounts),
+                    orders: ctx.link().callback(|orders| AppMsg::Orders(orders)),
+                    fills: ctx.link().callback(AppMsg::Fills),
+                    progress: ctx.link().callback(|progress| AppMsg::Progress(DataSource::Markets, progress)),
+                    decode_error: ctx.link().callback(AppMsg::DecodeFailed),
+                };
+
+                // Only the first load that succeeds keeps running as a
+                // subscription, refreshes just reload the orderbooks
+                let subscribe = !self.subscribed;
+                self.subscribed = true;
+                let cb_error = ctx.link().callback(move |e| AppMsg::MarketsFailed(e, subscribe));
+
+                spawn_fetch(
+                    sync_markets(
+                        self.source.clone(),
+                        self.markets.clone(),
+                        subscribe,
+                        callbacks,
+                    ),
+                    cb_error,
+                );
+            }
+            DataSource::Trades => {
+                let cb_trades = ctx.link().callback(|trades| AppMsg::Trades(trades));
+
+                spawn_fetch(fetch_trades(self.source.clone(), cb_trades), cb_error);
+            }
+            DataSource::TokenPrices => {
+                let cb_token_prices = ctx.link().callback(AppMsg::TokenPrices);
+
+                spawn_fetch(token_prices(self.source.clone(), cb_token_prices), cb_error);
+            }
+            DataSource::UserAccounts => {
+                let owner = match self.search_data.owner {
+                    Some(owner) if owner != Pubkey::default() => owner,
+                    _ => return,
+                };
+                let cb_accounts = ctx.link().callback(AppMsg::UserAccounts);
+
+                let accounts = self.markets
+                    .iter()
+                    .map(|item| item.user_account_address(&owner).to_string())
+                    .collect();
+
+                spawn_fetch(sync_accounts(self.source.clone(), accounts, cb_accounts), cb_error);
+            }
+            DataSource::Holdings => {
+                let owner = match self.search_data.owner {
+                    Some(owner) if owner != Pubkey::default() => owner.to_string(),
+                    _ => return,
+                };
+                let cb_holdings = ctx.link().callback(AppMsg::Holdings);
+
+                spawn_fetch(token_balances(self.source.clone(), owner, cb_holdings), cb_error);
+            }
+        }
+
+        self.statuses.insert(source, SourceStatus::Loading(None));
+    }
+}
+
+fn spawn_fetch(
+    fut: impl Future<Output = Result<(), FetchError>> + 'static,
+    cb_error: Callback<FetchError>,
+) {
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(e) = fut.await {
+            cb_error.emit(e);
+        }
+    });
+}
+
+async fn token_prices(
+    source: Rc<dyn MarketDataSource>,
+    cb_token_prices: Callback<ExchangeRates>,
+) -> Result<(), FetchError> {
+    // Costing only needs KI and GENE, a missing USD quote just keeps prices
+    // from being shown in USD
+    let sol_usd = match source.usd_price(SOL_MINT).await {
+        Ok(sol_usd) => Some(sol_usd),
+        Err(e) => {
+            console_log!("SOL/USD price failed: {}", e);
+
+            None
+        }
+    };
+
+    let rates = ExchangeRates {
+        ki: source.token_price(KI_MINT).await?,
+        gene: source.token_price(GENE_MINT).await?,
+        sol_usd,
+    };
+
+    cb_token_prices.emit(rates);
+
+    Ok(())
+}
+
+async fn token_balances(
+    source: Rc<dyn MarketDataSource>,
+    owner: String,
+    cb_holdings: Callback<HashMap<String, Decimal>>,
+) -> Result<(), FetchError> {
+    let holdings = source.token_balances(&owner).await?;
+
+    cb_holdings.emit(holdings);
+
+    Ok(())
 }
 
 async fn sync_accounts(
+    source: Rc<dyn MarketDataSource>,
     accounts: Vec<String>,
     cb_accounts: Callback<HashMap<Pubkey, UserAccountHeader>>,
-) {
+) -> Result<(), FetchError> {
     let mut results = HashMap::new();
 
     for chunk in accounts.chunks(100) {
-        let addresses = chunk.to_vec();
-
-        let body = json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "getMultipleAccounts",
-            "params": [
-                addresses,
-                { "encoding": "jsonParsed" }
-            ]
-        });
+        let accounts = source.get_multiple_accounts(chunk).await?;
 
-        let res = Request::post("https://try-rpc.mainnet.solana.blockdaemon.tech/")
-            .json(&body)
-            .unwrap()
-            .send()
-            .await
-            .unwrap()
-            .json::<JsonRpcResult<Vec<Option<UiAccount>>>>()
-            .await
-            .unwrap();
-
-        let iter = chunk
-            .iter()
-            .zip(res.result.value)
-            .filter_map(|(_, account)| {
+        let iter = accounts
+            .into_iter()
+            .filter_map(|account| {
                 let mut account = account?;
                 let account = UserAccount::from_buffer(&mut account.data).ok()?;
 
@@ -416,170 +921,230 @@ async fn sync_accounts(
     console_log!("Found {}/{} user accounts", results.len(), accounts.len());
 
     cb_accounts.emit(results);
+
+    Ok(())
+}
+
+/// Where `sync_markets` reports what it loads
+struct SyncCallbacks {
+    market_accounts: Callback<HashMap<String, MarketAccounts>>,
+    orders: Callback<Vec<SideUpdate>>,
+    fills: Callback<Fills>,
+    progress: Callback<(usize, usize)>,
+    decode_error: Callback<FetchError>,
 }
 
 async fn sync_markets(
+    source: Rc<dyn MarketDataSource>,
     markets: Vec<MagicEdenItem>,
-    cb_orders: Callback<HashMap<String, Vec<Listing>>>,
-) {
-    let mut results = HashMap::new();
-
-    for chunk in markets.chunks(100) {
-        let addresses = chunk
-            .iter()
-            .map(|item| item.asks_address.clone())
-            .collect::<Vec<_>>();
-        let body = json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "getMultipleAccounts",
-            "params": [
-                addresses,
-                { "encoding": "jsonParsed" }
-            ]
-        });
-
-        let res = Request::post("https://try-rpc.mainnet.solana.blockdaemon.tech/")
-            .json(&body)
-            .unwrap()
-            .send()
-            .await
-            .unwrap()
-            .json::<JsonRpcResult<Vec<UiAccount>>>()
-            .await
-            .unwrap();
-
-        let iter = chunk
-            .iter()
-            .zip(res.result.value)
-            .map(|(item, mut account)| {
-                let listings = Listings::from_buffer(&mut account.data).to_vec();
+    subscribe: bool,
+    callbacks: SyncCallbacks,
+) -> Result<(), FetchError> {
+    let mut market_accounts = HashMap::new();
+
+    // Chunks are sent concurrently (the RPC client caps how many are actually
+    // in flight) and each one is emitted as soon as it's loaded
+    let mut chunks = markets
+        .chunks(100)
+        .map(|chunk| load_market_accounts(source.as_ref(), chunk))
+        .collect::<FuturesUnordered<_>>();
+
+    let mut total = chunks.len() + (markets.len() + MARKETS_PER_CHUNK - 1) / MARKETS_PER_CHUNK;
+    let mut loaded = 0;
+
+    while let Some(accounts) = chunks.next().await {
+        let accounts = accounts?;
+
+        loaded += 1;
+        callbacks.progress.emit((loaded, total));
+        callbacks.market_accounts.emit(accounts.clone());
+        market_accounts.extend(accounts);
+    }
+    drop(chunks);
+
+    let accounts = market_accounts
+        .iter()
+        .map(|(token_address, accounts)| (token_address.clone(), accounts.clone()))
+        .collect::<Vec<_>>();
+
+    // Markets that failed to resolve don't get their slabs loaded
+    total = loaded + (accounts.len() + MARKETS_PER_CHUNK - 1) / MARKETS_PER_CHUNK;
+
+    let mut chunks = accounts
+        .chunks(MARKETS_PER_CHUNK)
+        .map(|chunk| load_orders(source.as_ref(), chunk))
+        .collect::<FuturesUnordered<_>>();
+
+    let mut decode_error = None;
+
+    while let Some(res) = chunks.next().await {
+        let (orders, fills, e) = res?;
+
+        loaded += 1;
+        callbacks.progress.emit((loaded, total));
+        callbacks.orders.emit(
+            orders
+                .into_iter()
+                .flat_map(|(token_address, orders)| orders.into_updates(token_address))
+                .collect(),
+        );
+        callbacks.fills.emit(fills);
+
+        if decode_error.is_none() {
+            decode_error = e;
+        }
+    }
+    drop(chunks);
 
-                (item.token_address.clone(), listings)
-            });
+    // A slab that can't be decoded only leaves its own market without orders,
+    // every other one still gets live updates
+    if let Some(e) = decode_error {
+        callbacks.decode_error.emit(e);
+    }
 
-        results.extend(iter);
+    // Recorded sources have no updates to subscribe to
+    if let Some(rpc) = source.rpc().filter(|_| subscribe) {
+        subscribe_markets(rpc, market_accounts, callbacks.orders).await;
     }
 
-    cb_orders.emit(results);
+    Ok(())
 }
 
-async fn fetch_trades(cb_trades: Callback<HashMap<String, Vec<Trade>>>) {
-    let trades: Vec<SftTrades> = Request::get("https://node-api.flipsidecrypto.com/api/v2/queries/b76d9ca9-cc22-48d8-9917-6760c1ec5a50/data/latest")
-        .send()
-        .await
-        .unwrap()
-        .json()
-        .await
-        .unwrap();
+async fn load_market_accounts(
+    source: &dyn MarketDataSource,
+    chunk: &[MagicEdenItem],
+) -> Result<HashMap<String, MarketAccounts>, FetchError> {
+    let mut market_accounts = HashMap::new();
 
-    let trades = trades
+    let addresses = chunk
+        .iter()
+        .map(|item| item.market_address.to_string())
+        .collect::<Vec<_>>();
+
+    let dex_states = source.get_multiple_accounts(&addresses)
+        .await?
         .into_iter()
-        .map(|item| (item.base_vault, item.trades))
-        .collect::<HashMap<_, _>>();
+        .map(|account| decode_dex_state(&account?.data).copied())
+        .collect::<Vec<_>>();
+
+    let addresses = dex_states
+        .iter()
+        .flatten()
+        .map(|dex_state| dex_state.orderbook.to_string())
+        .collect::<Vec<_>>();
+
+    let mut orderbooks = source.get_multiple_accounts(&addresses).await?.into_iter();
+
+    for (item, dex_state) in chunk.iter().zip(dex_states) {
+        let dex_state = match dex_state {
+            Some(dex_state) => dex_state,
+            None => {
+                console_log!("Market {} not found", item.market_address);
+                continue;
+            }
+        };
 
-    cb_trades.emit(trades);
-}
+        let orderbook = orderbooks.next().flatten();
+        let orderbook_state = match orderbook.as_ref().and_then(|account| decode_orderbook_state(&account.data)) {
+            Some(orderbook_state) => orderbook_state,
+            None => {
+                console_log!("Orderbook {} not found", dex_state.orderbook);
+                continue;
+            }
+        };
 
-#[derive(Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-struct SftTrades {
-    base_vault: String,
-    trades: Vec<Trade>,
-}
+        let accounts = MarketAccounts::new(item.market_address, &dex_state, orderbook_state);
 
-#[derive(Clone, Deserialize, PartialEq)]
-pub struct Trade {
-    ts: String,
-    amount: Decimal,
-    price: Decimal,
-}
+        for mismatch in accounts.mismatches(item) {
+            console_log!(
+                "{}: {} is {} but chain has {}",
+                item.market_address,
+                mismatch.field,
+                mismatch.declared,
+                mismatch.actual
+            );
+        }
 
-#[derive(Clone, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct MagicEdenItem {
-    base_vault_address: String,
-    asks_address: String,
-    #[serde(deserialize_with = "parse_base58_pubkey")]
-    market_address: Pubkey,
-    token_address: String,
-    token_image: String,
-    token_title: String,
-    collection: String,
-}
+        market_accounts.insert(item.token_address.clone(), accounts);
+    }
 
-#[derive(Deserialize, Debug)]
-struct JsonRpcResult<T> {
-    // id: u64,
-    // jsonrpc: String,
-    result: JsonRpcResultBody<T>,
+    Ok(market_accounts)
 }
 
-#[derive(Deserialize, Debug)]
-struct JsonRpcResultBody<T> {
-    // context: JsonRpcContext,
-    value: T,
-}
+/// Loads the orderbooks and pending fills of a chunk of markets. A market
+/// whose slabs can't be decoded is loaded with empty sides and the first such
+/// error is returned along with the orders, so it doesn't keep the rest from
+/// loading
+async fn load_orders(
+    source: &dyn MarketDataSource,
+    chunk: &[(String, MarketAccounts)],
+) -> Result<(HashMap<String, MarketOrders>, Fills, Option<FetchError>), FetchError> {
+    let mut results = HashMap::new();
+    let mut fills = HashMap::new();
+    let mut decode_error = None;
+
+    let addresses = chunk
+        .iter()
+        .flat_map(|(_, accounts)| {
+            [accounts.asks.to_string(), accounts.bids.to_string(), accounts.event_queue.to_string()]
+        })
+        .collect::<Vec<_>>();
+
+    let mut slabs = source.get_multiple_accounts(&addresses).await?.into_iter();
+
+    for (token_address, accounts) in chunk {
+        let mut decode = |address: &Pubkey, side| {
+            let mut account = slabs.next().flatten()?;
+
+            match Listings::from_buffer(&mut account.data, side) {
+                Ok(listings) => Some(listings.to_vec()),
+                Err(e) => {
+                    decode_error.get_or_insert(FetchError::SlabDecode {
+                        address: address.to_string(),
+                        reason: e.to_string(),
+                    });
+
+                    None
+                }
+            }
+        };
 
-// #[derive(Deserialize, Debug)]
-// #[serde(rename_all = "camelCase")]
-// struct JsonRpcContext {
-//     api_version: String,
-//     slot: u64,
-// }
-
-#[derive(Deserialize, Debug)]
-struct UiAccount {
-    lamports: u64,
-    #[serde(deserialize_with = "parse_account_data")]
-    data: Vec<u8>,
-    #[serde(deserialize_with = "parse_base58_pubkey")]
-    owner: Pubkey,
-    executable: bool,
-    #[serde(default)]
-    rent_epoch: u64,
-}
+        let asks = decode(&accounts.asks, Side::Asks).unwrap_or_default();
+        let bids = decode(&accounts.bids, Side::Bids).unwrap_or_default();
 
-impl From<UiAccount> for Account {
-    fn from(data: UiAccount) -> Self {
-        Account {
-            lamports: data.lamports,
-            data: data.data,
-            owner: data.owner,
-            executable: data.executable,
-            rent_epoch: data.rent_epoch,
-        }
-    }
-}
+        results.insert(token_address.clone(), MarketOrders { bids, asks });
 
-fn parse_account_data<'de, D: de::Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
-    let data: Vec<String> = Deserialize::deserialize(deserializer)?;
+        let market_fills = match slabs.next().flatten().map(|mut account| decode_fills(&mut account.data)) {
+            Some(Ok(market_fills)) => market_fills,
+            Some(Err(e)) => {
+                console_log!("Event queue {} failed to decode: {}", accounts.event_queue, e);
+                continue;
+            }
+            None => continue,
+        };
 
-    if data.len() != 2 {
-        return Err(de::Error::custom("Invalid array length"));
+        fills.insert(accounts.base_vault.to_string(), (accounts.fees, market_fills));
     }
 
-    match data[1].as_str() {
-        "base64" => base64::decode(&data[0]).map_err(|e| de::Error::custom(e.to_string())),
-        _ => Err(de::Error::custom(format!(
-            "Unsupported encoding: {}",
-            data[1]
-        ))),
-    }
+    Ok((results, fills, decode_error))
 }
 
-fn parse_base58_pubkey<'de, D: de::Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
-    let val: String = Deserialize::deserialize(deserializer)?;
-
-    Pubkey::from_str(&val).map_err(|e| de::Error::custom(e.to_string()))
+pub(crate) fn now_ts() -> DateTime<Utc> {
+    Utc.timestamp_millis_opt(js_sys::Date::now() as i64).unwrap()
 }
 
-#[derive(Deserialize)]
-struct JupiterPrice {
-    data: HashMap<String, JupiterPriceData>,
-}
+async fn fetch_trades(
+    source: Rc<dyn MarketDataSource>,
+    cb_trades: Callback<HashMap<String, Vec<Trade>>>,
+) -> Result<(), FetchError> {
+    let trades = source.trades().await?;
+
+    let trades = trades
+        .into_iter()
+        .map(|item| (item.base_vault, item.trades))
+        .collect::<HashMap<_, _>>();
+
+    cb_trades.emit(trades);
 
-#[derive(Deserialize)]
-struct JupiterPriceData {
-    price: Decimal,
+    Ok(())
 }
@@ -0,0 +1,319 @@
+use crate::error::send_json;
+use futures::channel::oneshot;
+use gloo_net::http::Request;
+use gloo_storage::{LocalStorage, Storage};
+use gloo_timers::future::TimeoutFuture;
+use magic_eden_sft_core::rpc::{
+    decode_accounts, decode_token_balances, get_multiple_accounts_request,
+    get_token_accounts_by_owner_request, UiAccount, DEFAULT_RPC_URL,
+};
+use magic_eden_sft_core::FetchError;
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+};
+use web_sys::UrlSearchParams;
+use yew::Callback;
+
+const DEFAULT_ENDPOINTS: &[&str] = &[DEFAULT_RPC_URL];
+const STORAGE_KEY: &str = "rpc_endpoints";
+const MAX_CONCURRENT_REQUESTS: usize = 2;
+const MAX_ATTEMPTS: u32 = 5;
+const BASE_RETRY_DELAY_MS: u32 = 500;
+const MAX_RETRY_DELAY_MS: u32 = 30_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RpcEndpoint {
+    pub url: String,
+    pub health: EndpointHealth,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum EndpointHealth {
+    #[default]
+    Unknown,
+    Ok {
+        latency_ms: u32,
+    },
+    Failed(String),
+}
+
+/// JSON-RPC client that spreads requests across the configured endpoints in
+/// round-robin order, falling back to the next one whenever a request fails.
+///
+/// Requests are capped to a few in flight at once, and when every endpoint
+/// fails with a retryable error the whole round is retried after a jittered
+/// exponential backoff (or whatever `Retry-After` asked for, if longer).
+#[derive(Clone)]
+pub struct RpcClient {
+    state: Rc<RefCell<RpcState>>,
+    permits: Rc<Permits>,
+    cb_health: Callback<Vec<RpcEndpoint>>,
+}
+
+struct RpcState {
+    endpoints: Vec<RpcEndpoint>,
+    next: usize,
+}
+
+impl RpcClient {
+    pub fn new(urls: Vec<String>, cb_health: Callback<Vec<RpcEndpoint>>) -> Self {
+        let client = Self {
+            state: Rc::new(RefCell::new(RpcState {
+                endpoints: vec![],
+                next: 0,
+            })),
+            permits: Rc::new(Permits::new(MAX_CONCURRENT_REQUESTS)),
+            cb_health,
+        };
+        client.set_endpoints(urls);
+
+        client
+    }
+
+    pub fn set_endpoints(&self, urls: Vec<String>) {
+        let mut state = self.state.borrow_mut();
+
+        state.endpoints = urls
+            .into_iter()
+            .map(|url| RpcEndpoint {
+                url,
+                health: EndpointHealth::Unknown,
+            })
+            .collect();
+        state.next = 0;
+    }
+
+    pub fn endpoints(&self) -> Vec<RpcEndpoint> {
+        self.state.borrow().endpoints.clone()
+    }
+
+    /// Websocket URL of the next endpoint in line
+    pub fn ws_url(&self) -> String {
+        let url = self
+            .endpoints_in_order()
+            .into_iter()
+            .next()
+            .map(|(_, url)| url)
+            .unwrap_or_else(|| DEFAULT_ENDPOINTS[0].to_string());
+
+        match url.strip_prefix("https://") {
+            Some(rest) => format!("wss://{rest}"),
+            None => url.replacen("http://", "ws://", 1),
+        }
+    }
+
+    pub async fn get_multiple_accounts(
+        &self,
+        addresses: &[String],
+    ) -> Result<Vec<Option<UiAccount>>, FetchError> {
+        let body = get_multiple_accounts_request(addresses);
+
+        decode_accounts(self.call(&body).await?)
+    }
+
+    /// Token balances of the owner, by mint
+    pub async fn get_token_balances(&self, owner: &str) -> Result<HashMap<String, Decimal>, FetchError> {
+        let body = get_token_accounts_by_owner_request(owner);
+
+        decode_token_balances(self.call(&body).await?)
+    }
+
+    /// Sends the request until it succeeds, fails with an error that can't be
+    /// retried, or runs out of attempts
+    pub async fn call<T: DeserializeOwned>(&self, body: &Value) -> Result<T, FetchError> {
+        let _permit = Permits::acquire(&self.permits).await;
+        let mut attempt = 0;
+
+        loop {
+            let e = match self.call_endpoints(body).await {
+                Ok(res) => return Ok(res),
+                Err(e) => e,
+            };
+
+            attempt += 1;
+            if !e.is_retryable() || attempt >= MAX_ATTEMPTS {
+                return Err(e);
+            }
+
+            let backoff = (BASE_RETRY_DELAY_MS << attempt).min(MAX_RETRY_DELAY_MS);
+            let jitter = (js_sys::Math::random() * backoff as f64 / 2.0) as u32;
+            let delay = match e {
+                FetchError::RateLimited {
+                    retry_after: Some(retry_after),
+                } => retry_after
+                    .saturating_mul(1_000)
+                    .max(backoff)
+                    .min(MAX_RETRY_DELAY_MS),
+                _ => backoff,
+            };
+
+            crate::console_log!("RPC request failed ({}), retrying in {}ms", e, delay + jitter);
+            TimeoutFuture::new(delay + jitter).await;
+        }
+    }
+
+    /// Sends the request to every endpoint, starting with the next one in line,
+    /// until one of them succeeds
+    async fn call_endpoints<T: DeserializeOwned>(&self, body: &Value) -> Result<T, FetchError> {
+        let mut last_error = FetchError::Http(String::from("No RPC endpoints configured"));
+
+        for (index, url) in self.endpoints_in_order() {
+            let started = js_sys::Date::now();
+
+            let res = match Request::post(&url).json(body) {
+                Ok(req) => send_json::<T>(req).await,
+                Err(e) => Err(FetchError::Json(e.to_string())),
+            };
+
+            let health = match &res {
+                Ok(_) => EndpointHealth::Ok {
+                    latency_ms: (js_sys::Date::now() - started) as u32,
+                },
+                Err(e) => EndpointHealth::Failed(e.to_string()),
+            };
+            self.set_health(index, &url, health);
+
+            match res {
+                Ok(res) => return Ok(res),
+                // Another endpoint won't fix a request the node rejected
+                Err(e) if !e.is_retryable() => return Err(e),
+                Err(e) => last_error = e,
+            }
+        }
+
+        Err(last_error)
+    }
+
+    fn endpoints_in_order(&self) -> Vec<(usize, String)> {
+        let mut state = self.state.borrow_mut();
+        let count = state.endpoints.len().max(1);
+        let start = state.next % count;
+        state.next = start + 1;
+
+        state
+            .endpoints
+            .iter()
+            .enumerate()
+            .cycle()
+            .skip(start)
+            .take(state.endpoints.len())
+            .map(|(index, endpoint)| (index, endpoint.url.clone()))
+            .collect()
+    }
+
+    fn set_health(&self, index: usize, url: &str, health: EndpointHealth) {
+        let endpoints = {
+            let mut state = self.state.borrow_mut();
+
+            // The endpoint list may have been replaced while the request was
+            // in flight
+            match state.endpoints.get_mut(index) {
+                Some(endpoint) if endpoint.url == url => endpoint.health = health,
+                _ => return,
+            }
+
+            state.endpoints.clone()
+        };
+
+        self.cb_health.emit(endpoints);
+    }
+}
+
+/// Async semaphore capping the number of requests in flight
+struct Permits {
+    available: Cell<usize>,
+    waiters: RefCell<VecDeque<oneshot::Sender<()>>>,
+}
+
+impl Permits {
+    fn new(count: usize) -> Self {
+        Self {
+            available: Cell::new(count),
+            waiters: RefCell::new(VecDeque::new()),
+        }
+    }
+
+    async fn acquire(permits: &Rc<Self>) -> Permit {
+        if permits.available.get() > 0 {
+            permits.available.set(permits.available.get() - 1);
+        } else {
+            let (tx, rx) = oneshot::channel();
+            permits.waiters.borrow_mut().push_back(tx);
+
+            // The permit is handed over by whoever releases it
+            let _ = rx.await;
+        }
+
+        Permit(permits.clone())
+    }
+
+    fn release(&self) {
+        loop {
+            let waiter = self.waiters.borrow_mut().pop_front();
+
+            match waiter {
+                // Waiters that gave up drop their receiver, so the permit goes
+                // to the next one in line
+                Some(waiter) => match waiter.send(()) {
+                    Ok(()) => return,
+                    Err(()) => continue,
+                },
+                None => {
+                    self.available.set(self.available.get() + 1);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+struct Permit(Rc<Permits>);
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+/// Endpoints from the `rpc` query parameter (comma separated) if present,
+/// otherwise the ones saved in local storage, otherwise the defaults
+pub fn load_endpoints() -> Vec<String> {
+    let from_query = web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .and_then(|search| UrlSearchParams::new_with_str(&search).ok())
+        .and_then(|params| params.get("rpc"))
+        .map(|urls| parse_endpoints(&urls, ','));
+
+    let endpoints = match from_query {
+        Some(endpoints) if !endpoints.is_empty() => endpoints,
+        _ => LocalStorage::get(STORAGE_KEY).unwrap_or_default(),
+    };
+
+    or_default_endpoints(endpoints)
+}
+
+pub fn or_default_endpoints(endpoints: Vec<String>) -> Vec<String> {
+    match endpoints.is_empty() {
+        true => DEFAULT_ENDPOINTS.iter().map(|url| url.to_string()).collect(),
+        false => endpoints,
+    }
+}
+
+pub fn save_endpoints(endpoints: &[String]) {
+    if let Err(e) = LocalStorage::set(STORAGE_KEY, endpoints) {
+        crate::console_log!("Failed to save RPC endpoints: {}", e);
+    }
+}
+
+pub fn parse_endpoints(urls: &str, separator: char) -> Vec<String> {
+    urls.split(separator)
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(String::from)
+        .collect()
+}
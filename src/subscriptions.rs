@@ -0,0 +1,50 @@
+use crate::console_log;
+use crate::rpc::RpcClient;
+use futures::{future, Sink, SinkExt, Stream, StreamExt};
+use gloo_net::websocket::{futures::WebSocket, Message, WebSocketError};
+use gloo_timers::future::TimeoutFuture;
+use magic_eden_sft_core::{keep_subscribed, MarketAccounts, OrderSubscriptions, SideUpdate};
+use std::collections::HashMap;
+use yew::Callback;
+
+/// Keeps the orderbooks up to date by subscribing to every bids and asks
+/// account. Each notification is emitted as a single side update, and the
+/// connection is re-established whenever it drops.
+pub async fn subscribe_markets(
+    rpc: RpcClient,
+    market_accounts: HashMap<String, MarketAccounts>,
+    cb_orders: Callback<Vec<SideUpdate>>,
+) {
+    let ws_url = rpc.ws_url();
+
+    keep_subscribed(
+        OrderSubscriptions::new(&market_accounts),
+        || future::ready(connect(&ws_url)),
+        TimeoutFuture::new,
+        |update| cb_orders.emit(vec![update]),
+        |message| console_log!("{}", message),
+    )
+    .await;
+}
+
+/// Opens the socket, sending and receiving text messages
+fn connect(
+    ws_url: &str,
+) -> Result<
+    (
+        impl Sink<String, Error = WebSocketError> + Unpin,
+        impl Stream<Item = Result<String, String>> + Unpin,
+    ),
+    String,
+> {
+    let ws = WebSocket::open(ws_url).map_err(|e| e.to_string())?;
+    let (write, read) = ws.split();
+
+    let write = write.with(|text: String| future::ready(Ok::<_, WebSocketError>(Message::Text(text))));
+    let read = read.map(|message| match message.map_err(|e| e.to_string())? {
+        Message::Text(message) => Ok(message),
+        Message::Bytes(bytes) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+    });
+
+    Ok((write, read))
+}
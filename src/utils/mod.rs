@@ -1,5 +0,0 @@
-mod listings;
-mod recipes;
-
-pub use self::listings::*;
-pub(crate) use self::recipes::Recipes;